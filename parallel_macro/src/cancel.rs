@@ -0,0 +1,39 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse::ParseStream, Expr, Ident, Result, Token};
+
+/// Parses an optional leading `cancel = <token>,` argument, shared by every async macro
+pub(crate) fn parse_cancel(input: ParseStream) -> Result<Option<Expr>> {
+    let fork = input.fork();
+    let is_cancel = fork.parse::<Ident>().is_ok_and(|ident| ident == "cancel") && fork.peek(Token![=]) && !fork.peek(Token![==]);
+    if !is_cancel {
+        return Ok(None);
+    }
+    
+    input.parse::<Ident>()?;
+    input.parse::<Token![=]>()?;
+    let token = input.parse()?;
+    input.parse::<Token![,]>()?;
+    
+    Ok(Some(token))
+}
+
+/// Wraps `body`, an expression evaluated in an async context, so that it is dropped as soon
+/// as the token is cancelled. With a token the result becomes `Result<_, Cancelled>`.
+pub(crate) fn with_cancel(cancel: &Option<Expr>, body: TokenStream) -> TokenStream {
+    match cancel {
+        None => body,
+        Some(token) => quote! {
+            {
+                let __cancel_token: &parallel_macro_core::CancellationToken = &(#token);
+                
+                // Check the token first so an already cancelled call doesn't start any work
+                tokio::select! {
+                    biased;
+                    _ = __cancel_token.cancelled() => Err(parallel_macro_core::Cancelled),
+                    value = async { #body } => Ok(value),
+                }
+            }
+        },
+    }
+}
@@ -0,0 +1,102 @@
+extern crate proc_macro;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{braced, parse::Parse, parse::ParseStream, parse_macro_input, Expr, Ident, Result, Token};
+
+use crate::cancel::{parse_cancel, with_cancel};
+use crate::duration::parse_duration;
+
+// circuit!("payments" within 2s { call() } else fallback)
+struct CircuitInput {
+    cancel: Option<Expr>,
+    name: Expr,
+    within: Option<proc_macro2::TokenStream>,
+    body: Expr,
+    fallback: Expr,
+}
+
+impl Parse for CircuitInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let cancel = parse_cancel(input)?;
+
+        let name = Expr::parse_without_eager_brace(input)?;
+
+        // Parse optional time limit
+        let within = if input.peek(Ident) {
+            let keyword = input.parse::<Ident>()?;
+            if keyword != "within" {
+                return Err(syn::Error::new(keyword.span(), "expected `within <duration>`"));
+            }
+            Some(parse_duration(input)?)
+        } else {
+            None
+        };
+
+        let content;
+        braced!(content in input);
+        let body = content.parse()?;
+
+        input.parse::<Token![else]>()?;
+        let fallback = input.parse()?;
+
+        Ok(CircuitInput { cancel, name, within, body, fallback })
+    }
+}
+
+/// Calls the body through the `CircuitBreaker` registered under `name`. Errors and
+/// timeouts count as failures; while the circuit is open the body isn't called and the
+/// `else` fallback is returned right away. The fallback is also used when the call runs
+/// out of time, so both have to be the body's `Result` type.
+pub(crate) fn circuit(input: TokenStream) -> TokenStream {
+    let CircuitInput { cancel, name, within, body, fallback } = parse_macro_input!(input as CircuitInput);
+
+    let call = match within {
+        Some(limit) => quote! {
+            match tokio::time::timeout(#limit, __call_future).await {
+                Ok(Ok(val)) => {
+                    __permit.success();
+                    Ok(val)
+                },
+                Ok(Err(e)) => {
+                    __permit.failure();
+                    Err(e)
+                },
+                Err(_) => {
+                    __permit.failure();
+                    #fallback
+                },
+            }
+        },
+        None => quote! {
+            match __call_future.await {
+                Ok(val) => {
+                    __permit.success();
+                    Ok(val)
+                },
+                Err(e) => {
+                    __permit.failure();
+                    Err(e)
+                },
+            }
+        },
+    };
+
+    // Prefixed so they don't shadow variables used in the body or the fallback
+    let guarded = quote! {
+        {
+            let __breaker = parallel_macro_core::CircuitBreaker::get(#name);
+            let __permit = __breaker.try_acquire();
+            match __permit {
+                Some(__permit) => {
+                    let __call_future = #body;
+                    #call
+                },
+                // Fail fast while the circuit is open
+                None => #fallback,
+            }
+        }
+    };
+    let expanded = with_cancel(&cancel, guarded);
+
+    TokenStream::from(expanded)
+}
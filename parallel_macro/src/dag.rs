@@ -0,0 +1,226 @@
+extern crate proc_macro;
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parenthesized, parse::Parse, parse::ParseStream, parse_macro_input, punctuated::Punctuated, Attribute, Expr,
+    Ident, Result, Token,
+};
+
+use crate::cancel::{parse_cancel, with_cancel};
+use crate::parallel::named_output;
+
+// A node of the graph: `posts(user) = get_posts(user.id)`
+struct Node {
+    name: Ident,
+    deps: Vec<Ident>,
+    expr: Expr,
+}
+
+struct DagInput {
+    // Set by a leading `#[dot]`: print the graph in DOT format while compiling
+    print_dot: bool,
+    cancel: Option<Expr>,
+    nodes: Vec<Node>,
+}
+
+impl Parse for Node {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse::<Ident>()?;
+
+        // Parse optional dependency list
+        let deps = if input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            Punctuated::<Ident, Token![,]>::parse_terminated(&content)?
+                .into_iter()
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        input.parse::<Token![=]>()?;
+        let expr = input.parse()?;
+
+        Ok(Node { name, deps, expr })
+    }
+}
+
+impl Parse for DagInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut print_dot = false;
+        for attr in input.call(Attribute::parse_outer)? {
+            if attr.path().is_ident("dot") {
+                print_dot = true;
+            } else {
+                return Err(syn::Error::new_spanned(attr, "unknown dag! option, expected `#[dot]`"));
+            }
+        }
+
+        let cancel = parse_cancel(input)?;
+
+        let mut nodes: Vec<Node> = Vec::new();
+        while !input.is_empty() {
+            let node = input.parse::<Node>()?;
+            if nodes.iter().any(|n| n.name == node.name) {
+                return Err(syn::Error::new(node.name.span(), format!("duplicate node `{}`", node.name)));
+            }
+            nodes.push(node);
+
+            if input.is_empty() {
+                break;
+            }
+
+            input.parse::<Token![;]>()?;
+        }
+
+        if nodes.is_empty() {
+            return Err(input.error("dag! needs at least one node"));
+        }
+
+        Ok(DagInput { print_dot, cancel, nodes })
+    }
+}
+
+/// Orders the nodes so every node comes after its dependencies.
+/// Fails on unknown dependencies and on cycles, pointing at the offending node.
+fn topological_order(nodes: &[Node]) -> Result<Vec<usize>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        Unvisited,
+        InProgress,
+        Done,
+    }
+
+    fn visit(index: usize, nodes: &[Node], marks: &mut [Mark], path: &mut Vec<usize>, order: &mut Vec<usize>) -> Result<()> {
+        match marks[index] {
+            Mark::Done => return Ok(()),
+            Mark::InProgress => {
+                // Report the cycle starting from the first node that closes it
+                let start = path.iter().position(|&i| i == index).unwrap_or(0);
+                let cycle = path[start..]
+                    .iter()
+                    .chain(std::iter::once(&index))
+                    .map(|&i| nodes[i].name.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                return Err(syn::Error::new(
+                    nodes[index].name.span(),
+                    format!("dependency cycle: {}", cycle),
+                ));
+            },
+            Mark::Unvisited => {},
+        }
+
+        marks[index] = Mark::InProgress;
+        path.push(index);
+        for dep in &nodes[index].deps {
+            let dep_index = nodes
+                .iter()
+                .position(|n| &n.name == dep)
+                .ok_or_else(|| syn::Error::new(dep.span(), format!("unknown node `{}`", dep)))?;
+            visit(dep_index, nodes, marks, path, order)?;
+        }
+        path.pop();
+        marks[index] = Mark::Done;
+        order.push(index);
+
+        Ok(())
+    }
+
+    let mut marks = vec![Mark::Unvisited; nodes.len()];
+    let mut order = Vec::with_capacity(nodes.len());
+    for index in 0..nodes.len() {
+        visit(index, nodes, &mut marks, &mut Vec::new(), &mut order)?;
+    }
+
+    Ok(order)
+}
+
+fn to_dot(nodes: &[Node]) -> String {
+    let mut dot = String::from("digraph dag {\n");
+    for node in nodes {
+        dot.push_str(&format!("    \"{}\";\n", node.name));
+        for dep in &node.deps {
+            dot.push_str(&format!("    \"{}\" -> \"{}\";\n", dep, node.name));
+        }
+    }
+    dot.push('}');
+    dot
+}
+
+/// Runs a graph of futures where each node names the nodes whose outputs it needs.
+/// Every node starts as soon as its dependencies are done, and the result is a struct
+/// with one field per node. Outputs of nodes that others depend on must be `Clone`.
+pub(crate) fn dag(input: TokenStream) -> TokenStream {
+    let DagInput { print_dot, cancel, nodes } = parse_macro_input!(input as DagInput);
+
+    let order = match topological_order(&nodes) {
+        Ok(order) => order,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    if print_dot {
+        eprintln!("{}", to_dot(&nodes));
+    }
+
+    let vars = nodes
+        .iter()
+        .map(|n| format_ident!("__dag_{}", n.name))
+        .collect::<Vec<_>>();
+    let has_dependents = nodes
+        .iter()
+        .map(|n| nodes.iter().any(|other| other.deps.contains(&n.name)))
+        .collect::<Vec<_>>();
+
+    // Define the nodes so that each one comes after the nodes it awaits
+    let definitions = order.iter().map(|&i| {
+        let node = &nodes[i];
+        let var = &vars[i];
+        let expr = &node.expr;
+        let deps = node.deps.iter().map(|dep| {
+            let dep_var = format_ident!("__dag_{}", dep);
+            quote! { let #dep = #dep_var.clone().await; }
+        });
+
+        let future = quote! {
+            async {
+                #(#deps)*
+                let node_future = #expr;
+                node_future.await
+            }
+        };
+
+        if has_dependents[i] {
+            // Shared lets several dependents await the same output
+            quote! { let #var = futures::future::FutureExt::shared(#future); }
+        } else {
+            quote! { let #var = #future; }
+        }
+    });
+
+    let joined = vars.iter().zip(has_dependents.iter()).map(|(var, shared)| {
+        if *shared {
+            quote! { #var.clone() }
+        } else {
+            quote! { #var }
+        }
+    });
+
+    let names = nodes.iter().map(|n| &n.name).collect::<Vec<_>>();
+    let output = named_output(&names, quote! { __values });
+
+    let run = quote! {
+        {
+            #(#definitions)*
+
+            // Joining every node drives the whole graph; a node waits only on its own dependencies
+            let __values = tokio::join!(
+                #(#joined),*
+            );
+            #output
+        }
+    };
+    let expanded = with_cancel(&cancel, run);
+
+    TokenStream::from(expanded)
+}
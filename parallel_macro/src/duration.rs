@@ -0,0 +1,69 @@
+use quote::quote;
+use syn::{parse::ParseStream, Expr, ExprLit, Lit, Result};
+
+// Units a duration literal can have, with the number of nanoseconds in each
+const UNITS: [(&str, u64); 6] = [
+    ("ns", 1),
+    ("us", 1_000),
+    ("ms", 1_000_000),
+    ("s", 1_000_000_000),
+    ("m", 60_000_000_000),
+    ("h", 3_600_000_000_000),
+];
+
+// Type suffixes that keep a literal a plain number of seconds (`5u64`)
+const NUMBER_SUFFIXES: [&str; 14] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", "f32", "f64",
+];
+
+/// Parses the duration slot of a timeout macro, see `duration`
+pub(crate) fn parse_duration(input: ParseStream) -> Result<proc_macro2::TokenStream> {
+    // The slot is usually followed by a `{ body }`, which must not be parsed as a struct literal
+    let expr = Expr::parse_without_eager_brace(input)?;
+    duration(&expr)
+}
+
+/// A `std::time::Duration` for a duration written as a literal with a unit (`250ms`, `2s`,
+/// `1m`, `1.5s`; also `ns`, `us` and `h`), a plain number of seconds (`2`, `0.5`, `5u64`),
+/// or any other expression that evaluates to a `Duration` or a number of seconds
+/// (see `parallel_macro_core::IntoDuration`).
+/// An unknown unit is an error pointing at the literal.
+pub(crate) fn duration(expr: &Expr) -> Result<proc_macro2::TokenStream> {
+    let (digits, suffix, span) = match expr {
+        Expr::Group(group) => return duration(&group.expr),
+        Expr::Lit(ExprLit { lit: Lit::Int(lit), .. }) => (lit.base10_digits(), lit.suffix(), lit.span()),
+        Expr::Lit(ExprLit { lit: Lit::Float(lit), .. }) => (lit.base10_digits(), lit.suffix(), lit.span()),
+        _ => return Ok(quote! { parallel_macro_core::IntoDuration::into_duration(#expr) }),
+    };
+
+    let nanos_per_unit = match suffix {
+        _ if suffix.is_empty() || NUMBER_SUFFIXES.contains(&suffix) => 1_000_000_000,
+        _ => match UNITS.iter().find(|(unit, _)| *unit == suffix) {
+            Some((_, nanos)) => *nanos,
+            None => {
+                let units = UNITS.iter().map(|(unit, _)| *unit).collect::<Vec<_>>().join(", ");
+                return Err(syn::Error::new(
+                    span,
+                    format!("unknown duration unit `{}`, expected one of {}", suffix, units),
+                ));
+            },
+        },
+    };
+
+    // Whole numbers are converted exactly, fractions through f64
+    let nanos = match digits.parse::<u64>() {
+        Ok(value) => value.checked_mul(nanos_per_unit),
+        Err(_) => {
+            let value = digits
+                .parse::<f64>()
+                .map_err(|_| syn::Error::new(span, "invalid duration"))?;
+            let nanos = (value * nanos_per_unit as f64).round();
+            (nanos < u64::MAX as f64).then_some(nanos as u64)
+        },
+    }
+    .ok_or_else(|| syn::Error::new(span, "duration is too long"))?;
+
+    let secs = nanos / 1_000_000_000;
+    let subsec_nanos = (nanos % 1_000_000_000) as u32;
+    Ok(quote! { std::time::Duration::new(#secs, #subsec_nanos) })
+}
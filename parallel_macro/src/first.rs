@@ -1,11 +1,15 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
-    braced, parse::Parse, parse::ParseStream, parse_macro_input, token, Expr, Result, Token,
+    braced, parse::Parse, parse::ParseStream, parse_macro_input, token, Expr, Ident, Result, Token,
 };
 
+use crate::cancel::{parse_cancel, with_cancel};
+use crate::duration::parse_duration;
+
 struct FirstInput {
+    cancel: Option<Expr>,
     futures_block: FuturesBlock,
     error_handler: ErrorHandler,
 }
@@ -48,6 +52,28 @@ impl Parse for FuturesBlock {
     }
 }
 
+impl FuturesBlock {
+    /// The collection in a `{ ..futures }` block, whose futures are only known at runtime.
+    /// A spread has to be the block's only branch.
+    fn spread(&self) -> Result<Option<&Expr>> {
+        let spreads = self.expressions.iter().filter_map(|expr| match expr {
+            Expr::Range(range) if range.start.is_none() && matches!(range.limits, syn::RangeLimits::HalfOpen(_)) => {
+                range.end.as_deref()
+            },
+            _ => None,
+        }).collect::<Vec<_>>();
+        
+        match spreads.as_slice() {
+            [] => Ok(None),
+            [futures] if self.expressions.len() == 1 => Ok(Some(futures)),
+            _ => Err(syn::Error::new(
+                self.brace_token.span.join(),
+                "`..futures` must be the only branch",
+            )),
+        }
+    }
+}
+
 impl Parse for ErrorHandler {
     fn parse(input: ParseStream) -> Result<Self> {
         let else_token = input.parse::<Token![else]>()?;
@@ -60,27 +86,102 @@ impl Parse for ErrorHandler {
     }
 }
 
+// hedge!(after 50ms, max = 3 { fetch(replica) })
+struct HedgeInput {
+    cancel: Option<Expr>,
+    delay: proc_macro2::TokenStream,
+    max: Option<Expr>,
+    body: Expr,
+}
+
+impl Parse for HedgeInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let cancel = parse_cancel(input)?;
+        
+        let keyword = input.parse::<Ident>()?;
+        if keyword != "after" {
+            return Err(syn::Error::new(keyword.span(), "expected `after <delay>`"));
+        }
+        let delay = parse_duration(input)?;
+        
+        // Parse optional limit on the number of copies
+        let max = if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            let key = input.parse::<Ident>()?;
+            if key != "max" {
+                return Err(syn::Error::new(key.span(), "expected `max = <copies>`"));
+            }
+            input.parse::<Token![=]>()?;
+            Some(Expr::parse_without_eager_brace(input)?)
+        } else {
+            None
+        };
+        
+        let content;
+        braced!(content in input);
+        let body = content.parse::<Expr>()?;
+        
+        Ok(HedgeInput {
+            cancel,
+            delay,
+            max,
+            body,
+        })
+    }
+}
+
 impl Parse for FirstInput {
     fn parse(input: ParseStream) -> Result<Self> {
+        let cancel = parse_cancel(input)?;
         let futures_block = input.parse::<FuturesBlock>()?;
         let error_handler = input.parse::<ErrorHandler>()?;
         
         Ok(FirstInput {
+            cancel,
             futures_block,
             error_handler,
         })
     }
 }
 
+/// Largest number of branches first! supports, see the OneOfN types in parallel_macro_core
+const MAX_FIRST_BRANCHES: usize = 12;
+
 pub(crate) fn first(input: TokenStream) -> TokenStream {
-    let FirstInput { futures_block, error_handler } = parse_macro_input!(input as FirstInput);
+    let FirstInput { cancel, futures_block, error_handler } = parse_macro_input!(input as FirstInput);
+    
+    // `{ ..futures }` races a runtime collection and returns the winner's index with its value
+    match futures_block.spread() {
+        Ok(Some(futures)) => {
+            let error_expr = &error_handler.error_expr;
+            let raced = quote! {
+                match parallel_macro_core::race(#futures).await {
+                    Some(winner) => Ok(winner),
+                    None => Err(#error_expr),
+                }
+            };
+            return TokenStream::from(with_cancel(&cancel, raced));
+        },
+        Ok(None) => {},
+        Err(err) => return err.to_compile_error().into(),
+    }
+    
+    if futures_block.expressions.len() > MAX_FIRST_BRANCHES {
+        return syn::Error::new(
+            futures_block.brace_token.span.join(),
+            format!("first! supports at most {} branches", MAX_FIRST_BRANCHES),
+        )
+        .to_compile_error()
+        .into();
+    }
     
     // let futures = futures_block.expressions.iter();
     let error_expr = &error_handler.error_expr;
     
     // Create a tokio::select! based implementation instead
+    // Prefixed so they don't shadow variables used in the branches or the `else` clause
     let future_vars = futures_block.expressions.iter().enumerate().map(|(i, _)| {
-        let var_name = format!("future_{}", i);
+        let var_name = format!("__future_{}", i);
         syn::Ident::new(&var_name, proc_macro2::Span::call_site())
     }).collect::<Vec<_>>();
     
@@ -88,15 +189,19 @@ pub(crate) fn first(input: TokenStream) -> TokenStream {
         quote! { let mut #var = #expr; }
     });
     
-    let select_branches = future_vars.iter().map(|var| {
+    // Tag the winner with its branch so every branch can have its own output type
+    let count = future_vars.len();
+    let one_of = format_ident!("OneOf{}", count);
+    let select_branches = future_vars.iter().enumerate().map(|(i, var)| {
+        let variant = format_ident!("{}", (b'A' + i as u8) as char);
         quote! { 
             val = #var => {
-                return Ok(val);
+                return Ok(parallel_macro_core::#one_of::#variant(val));
             }
         }
     });
     
-    let expanded = quote! {
+    let raced = quote! {
         {
             async move {
                 #(#future_assignments)*
@@ -112,6 +217,92 @@ pub(crate) fn first(input: TokenStream) -> TokenStream {
             }.await
         }
     };
+    let expanded = with_cancel(&cancel, raced);
+    
+    TokenStream::from(expanded)
+}
+
+/// Like `first!`, but only a successful branch wins: branches that return `Err` are
+/// skipped while the others keep racing. If every branch fails, the `else` handler is
+/// called with a `MultiError` holding all of the errors.
+pub(crate) fn first_ok(input: TokenStream) -> TokenStream {
+    let FirstInput { cancel, futures_block, error_handler } = parse_macro_input!(input as FirstInput);
+    
+    let error_expr = &error_handler.error_expr;
+    
+    match futures_block.spread() {
+        Ok(Some(futures)) => {
+            let raced = quote! {
+                parallel_macro_core::race_ok(#futures).await.map_err(#error_expr)
+            };
+            return TokenStream::from(with_cancel(&cancel, raced));
+        },
+        Ok(None) => {},
+        Err(err) => return err.to_compile_error().into(),
+    }
+    
+    // Prefixed so they don't shadow variables used in the branches or the `else` handler
+    let future_vars = futures_block.expressions.iter().enumerate().map(|(i, _)| {
+        let var_name = format!("__future_{}", i);
+        syn::Ident::new(&var_name, proc_macro2::Span::call_site())
+    }).collect::<Vec<_>>();
+    let done_vars = futures_block.expressions.iter().enumerate().map(|(i, _)| {
+        let var_name = format!("__done_{}", i);
+        syn::Ident::new(&var_name, proc_macro2::Span::call_site())
+    }).collect::<Vec<_>>();
+    
+    let future_assignments = futures_block.expressions.iter().zip(future_vars.iter().zip(done_vars.iter())).map(|(expr, (var, done))| {
+        quote! {
+            let mut #var = std::pin::pin!(#expr);
+            let mut #done = false;
+        }
+    });
+    
+    // A finished branch is disabled so select! keeps waiting on the others
+    let select_branches = future_vars.iter().zip(done_vars.iter()).enumerate().map(|(i, (var, done))| {
+        quote! {
+            result = &mut #var, if !#done => {
+                #done = true;
+                match result {
+                    Ok(val) => break Ok(val),
+                    Err(err) => __errors.push(#i, None, err),
+                }
+            }
+        }
+    });
+    
+    let raced = quote! {
+        {
+            let mut __errors = parallel_macro_core::MultiError::new();
+            #(#future_assignments)*
+            
+            loop {
+                tokio::select! {
+                    #(#select_branches)*
+                    else => {
+                        break Err((#error_expr)(__errors));
+                    }
+                }
+            }
+        }
+    };
+    let expanded = with_cancel(&cancel, raced);
     
     TokenStream::from(expanded)
-}
\ No newline at end of file
+}
+
+/// Starts `body`, and another copy of it each time `after` elapses without any copy
+/// finishing, up to `max` copies (2 by default). The first copy to finish wins and the
+/// others are dropped.
+pub(crate) fn hedge(input: TokenStream) -> TokenStream {
+    let HedgeInput { cancel, delay, max, body } = parse_macro_input!(input as HedgeInput);
+    
+    let max = max.map_or_else(|| quote! { 2 }, |max| quote! { #max });
+    
+    let raced = quote! {
+        parallel_macro_core::hedge(#delay, #max, || #body).await
+    };
+    let expanded = with_cancel(&cancel, raced);
+    
+    TokenStream::from(expanded)
+}
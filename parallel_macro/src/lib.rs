@@ -1,9 +1,16 @@
 // src/lib.rs
 use proc_macro::TokenStream;
 
+mod cancel;
+mod circuit;
+mod dag;
+mod duration;
 mod parallel;
+mod par;
 mod timeout;
 mod first;
+mod quorum;
+mod retry;
 mod timeout_with_result;
 
 #[proc_macro]
@@ -11,6 +18,36 @@ pub fn parallel(input: TokenStream) -> TokenStream {
     parallel::parallel(input)
 }
 
+#[proc_macro]
+pub fn try_parallel(input: TokenStream) -> TokenStream {
+    parallel::try_parallel(input)
+}
+
+#[proc_macro]
+pub fn parallel_spawn(input: TokenStream) -> TokenStream {
+    parallel::parallel_spawn(input)
+}
+
+#[proc_macro]
+pub fn parallel_settled(input: TokenStream) -> TokenStream {
+    parallel::parallel_settled(input)
+}
+
+#[proc_macro]
+pub fn parallel_collect(input: TokenStream) -> TokenStream {
+    parallel::parallel_collect(input)
+}
+
+#[proc_macro]
+pub fn par(input: TokenStream) -> TokenStream {
+    par::par(input)
+}
+
+#[proc_macro]
+pub fn dag(input: TokenStream) -> TokenStream {
+    dag::dag(input)
+}
+
 #[proc_macro]
 pub fn timeout(input: TokenStream) -> TokenStream {
     timeout::timeout(input)
@@ -31,6 +68,36 @@ pub fn first(input: TokenStream) -> TokenStream {
     first::first(input)
 }
 
+#[proc_macro]
+pub fn first_ok(input: TokenStream) -> TokenStream {
+    first::first_ok(input)
+}
+
+#[proc_macro]
+pub fn hedge(input: TokenStream) -> TokenStream {
+    first::hedge(input)
+}
+
+#[proc_macro]
+pub fn quorum(input: TokenStream) -> TokenStream {
+    quorum::quorum(input)
+}
+
+#[proc_macro]
+pub fn vote(input: TokenStream) -> TokenStream {
+    quorum::vote(input)
+}
+
+#[proc_macro]
+pub fn retry(input: TokenStream) -> TokenStream {
+    retry::retry(input)
+}
+
+#[proc_macro]
+pub fn circuit(input: TokenStream) -> TokenStream {
+    circuit::circuit(input)
+}
+
 #[proc_macro]
 pub fn timeout_with_result(input: TokenStream) -> TokenStream {
     timeout_with_result::timeout_with_result(input)
@@ -0,0 +1,60 @@
+extern crate proc_macro;
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse_macro_input;
+
+use crate::parallel::{shape_output, ParallelInput};
+
+/// Synchronous counterpart of `parallel!` for CPU-bound work.
+/// Every branch runs on its own scoped thread, so branches can borrow from the caller's stack.
+/// A panic in a branch is re-raised on the calling thread with the branch index.
+pub(crate) fn par(input: TokenStream) -> TokenStream {
+    let parallel_input = parse_macro_input!(input as ParallelInput);
+    
+    // Plain closures on OS threads can't be interrupted
+    if let Some(cancel) = &parallel_input.cancel {
+        return syn::Error::new_spanned(cancel, "par! branches can't be cancelled; use parallel_spawn! for cancellable work")
+            .to_compile_error()
+            .into();
+    }
+    
+    // Error recovery and timeouts only make sense for futures
+    for branch in &parallel_input.branches {
+        if branch.recover.is_some() || branch.timeout.is_some() {
+            return syn::Error::new_spanned(&branch.expr, "`or`, `or_else` and `within` are not supported in par!")
+                .to_compile_error()
+                .into();
+        }
+    }
+    
+    let handles = (0..parallel_input.branches.len())
+        .map(|i| format_ident!("__handle_{}", i))
+        .collect::<Vec<_>>();
+    let spawns = parallel_input.branches.iter().zip(handles.iter()).map(|(branch, handle)| {
+        let expr = &branch.expr;
+        quote! { let #handle = __scope.spawn(|| #expr); }
+    });
+    let joins = handles.iter().enumerate().map(|(i, handle)| {
+        quote! {
+            match #handle.join() {
+                Ok(value) => value,
+                Err(payload) => parallel_macro_core::resume_branch_panic(#i, payload),
+            }
+        }
+    });
+    let output = shape_output(&parallel_input, quote! { __values });
+    
+    let expanded = quote! {
+        {
+            // Scoped threads are joined before scope() returns, so they may borrow locals
+            let __values = std::thread::scope(|__scope| {
+                #(#spawns)*
+                
+                (#(#joins,)*)
+            });
+            #output
+        }
+    };
+    
+    TokenStream::from(expanded)
+}
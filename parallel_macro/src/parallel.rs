@@ -1,19 +1,179 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, parse::Parse, parse::ParseStream, Expr, Result, Token};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse::Parse, parse::ParseStream, Expr, Ident, Pat, Result, Token};
 
-struct ParallelInput {
-    expressions: Vec<Expr>,
+use crate::cancel::{parse_cancel, with_cancel};
+use crate::duration::parse_duration;
+use crate::timeout::timeout_fallback_body;
+
+// A single branch, optionally named: `posts: get_posts(id)`
+pub(crate) struct Branch {
+    pub(crate) name: Option<Ident>,
+    pub(crate) expr: Expr,
+    // Optional `or <default>` / `or_else <handler>` suffix; the error type must be `Debug`
+    // so the swallowed error can be passed to the error hook
+    pub(crate) recover: Option<BranchRecover>,
+    // Optional `=> within <secs> else <fallback>` suffix
+    pub(crate) timeout: Option<BranchTimeout>,
+}
+
+pub(crate) enum BranchRecover {
+    // `or <default>`: replace an `Err` with the default value
+    Default(Expr),
+    // `or_else <handler>`: call the handler with the error
+    OrElse(Expr),
+}
+
+pub(crate) struct BranchTimeout {
+    duration: proc_macro2::TokenStream,
+    fallback: Expr,
+}
+
+pub(crate) struct ParallelInput {
+    // Optional leading `cancel = <token>,`
+    pub(crate) cancel: Option<Expr>,
+    pub(crate) branches: Vec<Branch>,
+}
+
+// Input for the iterator form: `for <pat> in <iter>, limit = <n> => <body>`
+struct ParallelForInput {
+    cancel: Option<Expr>,
+    pat: Pat,
+    iter: Expr,
+    limit: Option<Expr>,
+    body: Expr,
+}
+
+// Input for parallel_settled!: an optional `within <secs>;` prefix followed by the branches
+struct ParallelSettledInput {
+    limit: Option<proc_macro2::TokenStream>,
+    parallel_input: ParallelInput,
+}
+
+enum ParallelMacroInput {
+    List(ParallelInput),
+    ForEach(Box<ParallelForInput>),
+}
+
+impl Parse for Branch {
+    fn parse(input: ParseStream) -> Result<Self> {
+        // `ident:` prefix, but not the start of a path like `module::func()`
+        let name = if input.peek(Ident) && input.peek2(Token![:]) && !input.peek2(Token![::]) {
+            let name = input.parse::<Ident>()?;
+            input.parse::<Token![:]>()?;
+            Some(name)
+        } else {
+            None
+        };
+        
+        let expr = input.parse()?;
+        
+        // Parse optional error recovery
+        let recover = if input.peek(Ident) {
+            let keyword = input.parse::<Ident>()?;
+            if keyword == "or" {
+                Some(BranchRecover::Default(input.parse()?))
+            } else if keyword == "or_else" {
+                Some(BranchRecover::OrElse(input.parse()?))
+            } else {
+                return Err(syn::Error::new(keyword.span(), "expected `or <default>` or `or_else <handler>`"));
+            }
+        } else {
+            None
+        };
+        
+        // Parse optional per-branch timeout
+        let timeout = if input.peek(Token![=>]) {
+            input.parse::<Token![=>]>()?;
+            let keyword = input.parse::<Ident>()?;
+            if keyword != "within" {
+                return Err(syn::Error::new(keyword.span(), "expected `within <duration> else <fallback>`"));
+            }
+            let duration = parse_duration(input)?;
+            input.parse::<Token![else]>()?;
+            let fallback = input.parse()?;
+            Some(BranchTimeout { duration, fallback })
+        } else {
+            None
+        };
+        
+        Ok(Branch { name, expr, recover, timeout })
+    }
+}
+
+impl Branch {
+    /// The future to run for branch number `index`. Recovery and timeouts wrap the
+    /// expression in async blocks; `move_captures` makes those blocks `async move`,
+    /// which spawned branches need to be `'static`.
+    pub(crate) fn future(&self, index: usize, move_captures: bool) -> proc_macro2::TokenStream {
+        let capture = if move_captures { quote! { move } } else { quote! {} };
+        let expr = &self.expr;
+        
+        let recovered = match &self.recover {
+            None => quote! { #expr },
+            Some(recover) => {
+                let name = match &self.name {
+                    Some(name) => {
+                        let name = name.to_string();
+                        quote! { Some(#name) }
+                    },
+                    None => quote! { None },
+                };
+                let on_error = match recover {
+                    BranchRecover::Default(default) => quote! { #default },
+                    BranchRecover::OrElse(handler) => quote! { (#handler)(err) },
+                };
+                quote! {
+                    async #capture {
+                        let branch_future = #expr;
+                        match branch_future.await {
+                            Ok(value) => value,
+                            Err(err) => {
+                                // Let the registered hook see the error before it is replaced
+                                parallel_macro_core::report_swallowed_error(#index, #name, &err);
+                                #on_error
+                            }
+                        }
+                    }
+                }
+            }
+        };
+        
+        match &self.timeout {
+            None => recovered,
+            Some(BranchTimeout { duration, fallback }) => {
+                let recovered = syn::parse_quote! { #recovered };
+                let timeout_body = timeout_fallback_body(duration, &recovered, fallback);
+                quote! { async #capture { #timeout_body } }
+            }
+        }
+    }
 }
 
 impl Parse for ParallelInput {
     fn parse(input: ParseStream) -> Result<Self> {
-        let mut expressions = Vec::new();
+        let cancel = parse_cancel(input)?;
+        let mut branches: Vec<Branch> = Vec::new();
         
         while !input.is_empty() {
-            let expr = input.parse::<Expr>()?;
-            expressions.push(expr);
+            let branch = input.parse::<Branch>()?;
+            
+            // Either every branch is named or none of them are
+            if let Some(first) = branches.first() {
+                if first.name.is_some() != branch.name.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        &branch.expr,
+                        "either all branches must be named or none of them",
+                    ));
+                }
+            }
+            if let Some(name) = &branch.name {
+                if branches.iter().any(|b| b.name.as_ref() == Some(name)) {
+                    return Err(syn::Error::new(name.span(), format!("duplicate branch name `{}`", name)));
+                }
+            }
+            branches.push(branch);
             
             if input.is_empty() {
                 break;
@@ -22,17 +182,150 @@ impl Parse for ParallelInput {
             input.parse::<Token![,]>()?;
         }
         
-        Ok(ParallelInput { expressions })
+        Ok(ParallelInput { cancel, branches })
+    }
+}
+
+impl ParallelInput {
+    pub(crate) fn futures(&self) -> impl Iterator<Item = proc_macro2::TokenStream> + '_ {
+        self.branches.iter().enumerate().map(|(i, b)| b.future(i, false))
+    }
+    
+    pub(crate) fn names(&self) -> Option<Vec<&Ident>> {
+        self.branches.iter().map(|b| b.name.as_ref()).collect()
+    }
+}
+
+/// Turns the tuple produced by joining the branches into the macro's output.
+/// Unnamed branches keep the tuple; named branches are moved into a struct
+/// with one field per branch, so `output.posts` can't be mixed up with `output.followers`.
+pub(crate) fn shape_output(input: &ParallelInput, tuple: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    match input.names() {
+        Some(names) if !names.is_empty() => named_output(&names, tuple),
+        _ => tuple,
+    }
+}
+
+/// Moves the values of `tuple` into a struct with one field per name, in order
+pub(crate) fn named_output(names: &[&Ident], tuple: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let generics = (0..names.len())
+        .map(|i| format_ident!("T{}", i))
+        .collect::<Vec<_>>();
+    let vars = (0..names.len())
+        .map(|i| format_ident!("__branch_{}", i))
+        .collect::<Vec<_>>();
+    
+    quote! {
+        {
+            #[derive(Debug)]
+            struct ParallelOutput<#(#generics),*> {
+                #(#names: #generics),*
+            }
+            
+            let (#(#vars,)*) = #tuple;
+            ParallelOutput {
+                #(#names: #vars),*
+            }
+        }
+    }
+}
+
+impl Parse for ParallelForInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let cancel = parse_cancel(input)?;
+        input.parse::<Token![for]>()?;
+        let pat = Pat::parse_single(input)?;
+        input.parse::<Token![in]>()?;
+        let iter = input.parse()?;
+        
+        // Parse optional concurrency limit
+        let limit = if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            let key = input.parse::<Ident>()?;
+            if key != "limit" {
+                return Err(syn::Error::new(key.span(), "expected `limit = <n>`"));
+            }
+            input.parse::<Token![=]>()?;
+            let limit = input.parse::<Expr>()?;
+            // With no futures in flight the stream would never make progress
+            if let Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(lit), .. }) = &limit {
+                if lit.base10_parse::<usize>()? == 0 {
+                    return Err(syn::Error::new(lit.span(), "`limit` must be at least 1"));
+                }
+            }
+            Some(limit)
+        } else {
+            None
+        };
+        
+        input.parse::<Token![=>]>()?;
+        let body = input.parse()?;
+        
+        Ok(ParallelForInput {
+            cancel,
+            pat,
+            iter,
+            limit,
+            body,
+        })
+    }
+}
+
+impl Parse for ParallelSettledInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let cancel = parse_cancel(input)?;
+        
+        // `within` must be followed by the start of a duration (`250ms`, `limit`,
+        // `Duration::from_secs(1)`); `within(..)`, `within.x` or `within: ..` is a first branch
+        let fork = input.fork();
+        let has_limit = fork.parse::<Ident>().is_ok_and(|ident| ident == "within")
+            && (fork.peek(syn::Lit) || fork.peek(Ident));
+        
+        let limit = if has_limit {
+            input.parse::<Ident>()?;
+            let limit = parse_duration(input)?;
+            input.parse::<Token![;]>()?;
+            Some(limit)
+        } else {
+            None
+        };
+        
+        let mut parallel_input: ParallelInput = input.parse()?;
+        if cancel.is_some() {
+            parallel_input.cancel = cancel;
+        }
+        
+        Ok(ParallelSettledInput {
+            limit,
+            parallel_input,
+        })
+    }
+}
+
+impl Parse for ParallelMacroInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        // Look past an optional `cancel = <token>,` to tell the two forms apart
+        let fork = input.fork();
+        parse_cancel(&fork)?;
+        
+        if fork.peek(Token![for]) {
+            Ok(ParallelMacroInput::ForEach(Box::new(input.parse()?)))
+        } else {
+            Ok(ParallelMacroInput::List(input.parse()?))
+        }
     }
 }
 
 pub fn parallel(input: TokenStream) -> TokenStream {
-    let ParallelInput { expressions } = parse_macro_input!(input as ParallelInput);
+    let parallel_input = match parse_macro_input!(input as ParallelMacroInput) {
+        ParallelMacroInput::List(parallel_input) => parallel_input,
+        ParallelMacroInput::ForEach(for_input) => return parallel_for(*for_input),
+    };
     
     // Generate a tuple with the correct types
-    let expr_tokens = expressions.iter();
+    let expr_tokens = parallel_input.futures();
     
-    let expanded = quote! {
+    let joined = quote! {
         {
             use futures::future::Future;
             use futures::future::join_all;
@@ -43,6 +336,170 @@ pub fn parallel(input: TokenStream) -> TokenStream {
             )
         }
     };
+    let expanded = with_cancel(&parallel_input.cancel, shape_output(&parallel_input, joined));
+    
+    TokenStream::from(expanded)
+}
+
+/// Iterator form of `parallel!`: runs `body` for every item, at most `limit` at a time,
+/// and collects the outputs into a `Vec` in input order.
+fn parallel_for(input: ParallelForInput) -> TokenStream {
+    let ParallelForInput { cancel, pat, iter, limit, body } = input;
+    
+    let collected = match limit {
+        Some(limit) => quote! {
+            {
+                use futures::stream::StreamExt;
+                
+                let __limit: usize = #limit;
+                assert!(__limit > 0, "parallel! `limit` must be at least 1");
+                
+                // buffered() keeps at most `limit` futures in flight and yields them in order
+                futures::stream::iter(#iter)
+                    .map(|#pat| #body)
+                    .buffered(__limit)
+                    .collect::<Vec<_>>()
+                    .await
+            }
+        },
+        None => quote! {
+            {
+                futures::future::join_all(
+                    ::std::iter::IntoIterator::into_iter(#iter).map(|#pat| #body)
+                ).await
+            }
+        },
+    };
+    let expanded = with_cancel(&cancel, collected);
+    
+    TokenStream::from(expanded)
+}
+
+/// Like `parallel!`, but for futures that return `Result`.
+/// Resolves to `Ok((a, b, ...))` once every branch succeeds, or to the first `Err`,
+/// dropping the branches that are still running.
+pub fn try_parallel(input: TokenStream) -> TokenStream {
+    let parallel_input = parse_macro_input!(input as ParallelInput);
+    
+    let expr_tokens = parallel_input.futures();
+    let output = shape_output(&parallel_input, quote! { __values });
+    
+    let joined = quote! {
+        {
+            // try_join! returns as soon as one branch fails and drops the rest
+            tokio::try_join!(
+                #(#expr_tokens),*
+            ).map(|__values| #output)
+        }
+    };
+    let expanded = with_cancel(&parallel_input.cancel, joined);
+    
+    TokenStream::from(expanded)
+}
+
+/// Like `parallel!`, but every branch runs on its own tokio task, so CPU-heavy branches
+/// can make progress on other worker threads. Resolves to `Ok((a, b, ...))`, or to a
+/// `SpawnError` naming the first branch that panicked. Dropping the future aborts the branches.
+pub fn parallel_spawn(input: TokenStream) -> TokenStream {
+    let parallel_input = parse_macro_input!(input as ParallelInput);
+    
+    let spawned = parallel_input.branches.iter().enumerate().map(|(i, branch)| {
+        let future = branch.future(i, true);
+        quote! { parallel_macro_core::spawn_branch(#i, #future) }
+    });
+    let output = shape_output(&parallel_input, quote! { __values });
+    
+    let joined = quote! {
+        {
+            // Spawned tasks are aborted as soon as their handles are dropped
+            tokio::try_join!(
+                #(#spawned),*
+            ).map(|__values| #output)
+        }
+    };
+    let expanded = with_cancel(&parallel_input.cancel, joined);
+    
+    TokenStream::from(expanded)
+}
+
+/// Like `parallel!`, but waits for every `Result`-returning branch and reports each outcome
+/// as a `Settled` value, so a failed, panicked or timed out branch doesn't hide the others.
+pub fn parallel_settled(input: TokenStream) -> TokenStream {
+    let ParallelSettledInput { limit, parallel_input } = parse_macro_input!(input as ParallelSettledInput);
+    
+    // Every branch gets the same deadline, counted from the start of the call
+    let limit = match limit {
+        Some(limit) => quote! { Some(#limit) },
+        None => quote! { None },
+    };
+    let settled = parallel_input.futures().map(|expr| {
+        quote! { parallel_macro_core::settle(#expr, __limit) }
+    });
+    let output = shape_output(&parallel_input, quote! { __values });
+    
+    let joined = quote! {
+        {
+            let __limit = #limit;
+            let __values = tokio::join!(
+                #(#settled),*
+            );
+            #output
+        }
+    };
+    let expanded = with_cancel(&parallel_input.cancel, joined);
+    
+    TokenStream::from(expanded)
+}
+
+/// Like `try_parallel!`, but waits for every branch and collects all of the errors.
+/// Resolves to `Ok((a, b, ...))` if every branch succeeds, or to a `MultiError` with
+/// one entry per failed branch.
+pub fn parallel_collect(input: TokenStream) -> TokenStream {
+    let parallel_input = parse_macro_input!(input as ParallelInput);
+    
+    let count = parallel_input.branches.len();
+    let results = (0..count).map(|i| format_ident!("__result_{}", i)).collect::<Vec<_>>();
+    let values = (0..count).map(|i| format_ident!("__value_{}", i)).collect::<Vec<_>>();
+    let expr_tokens = parallel_input.futures();
+    
+    let collect = parallel_input.branches.iter().enumerate().map(|(i, branch)| {
+        let result = &results[i];
+        let value = &values[i];
+        let name = match &branch.name {
+            Some(name) => {
+                let name = name.to_string();
+                quote! { Some(#name) }
+            },
+            None => quote! { None },
+        };
+        quote! {
+            let #value = match #result {
+                Ok(value) => Some(value),
+                Err(err) => {
+                    __errors.push(#i, #name, err);
+                    None
+                }
+            };
+        }
+    });
+    let output = shape_output(&parallel_input, quote! { (#(#values,)*) });
+    
+    let joined = quote! {
+        {
+            let (#(#results,)*) = tokio::join!(
+                #(#expr_tokens),*
+            );
+            
+            let mut __errors = parallel_macro_core::MultiError::new();
+            #(#collect)*
+            
+            match (#(#values,)*) {
+                (#(Some(#values),)*) => Ok(#output),
+                _ => Err(__errors),
+            }
+        }
+    };
+    let expanded = with_cancel(&parallel_input.cancel, joined);
     
     TokenStream::from(expanded)
 }
\ No newline at end of file
@@ -0,0 +1,164 @@
+extern crate proc_macro;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    braced, parse::Parse, parse::ParseStream, parse_macro_input, punctuated::Punctuated, Expr, Ident, Result, Token,
+};
+
+use crate::cancel::{parse_cancel, with_cancel};
+use crate::duration::parse_duration;
+
+// quorum!(2 of { write(a), write(b), write(c) } within 1s)
+struct QuorumInput {
+    cancel: Option<Expr>,
+    required: Expr,
+    branches: Vec<Expr>,
+    within: Option<proc_macro2::TokenStream>,
+}
+
+impl Parse for QuorumInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let cancel = parse_cancel(input)?;
+
+        let required = input.parse::<Expr>()?;
+        let keyword = input.parse::<Ident>()?;
+        if keyword != "of" {
+            return Err(syn::Error::new(keyword.span(), "expected `<n> of { ... }`"));
+        }
+
+        let content;
+        let brace_token = braced!(content in input);
+        let branches = Punctuated::<Expr, Token![,]>::parse_terminated(&content)?
+            .into_iter()
+            .collect::<Vec<_>>();
+        if branches.is_empty() {
+            return Err(syn::Error::new(brace_token.span.join(), "quorum! needs at least one branch"));
+        }
+        // A computed `n` is checked when the call runs
+        if let Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(lit), .. }) = &required {
+            let n = lit.base10_parse::<usize>()?;
+            if n == 0 || n > branches.len() {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    format!("quorum needs between 1 and {} (the number of branches)", branches.len()),
+                ));
+            }
+        }
+
+        // Parse optional time limit
+        let within = if input.is_empty() {
+            None
+        } else {
+            let keyword = input.parse::<Ident>()?;
+            if keyword != "within" {
+                return Err(syn::Error::new(keyword.span(), "expected `within <duration>`"));
+            }
+            Some(parse_duration(input)?)
+        };
+
+        Ok(QuorumInput { cancel, required, branches, within })
+    }
+}
+
+// vote! { compare = |a, b| a.id == b.id, source_a(), source_b(), source_c() }
+struct VoteInput {
+    cancel: Option<Expr>,
+    compare: Option<Expr>,
+    branches: Vec<Expr>,
+}
+
+impl Parse for VoteInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let cancel = parse_cancel(input)?;
+
+        // Parse optional comparator
+        let fork = input.fork();
+        let has_compare = fork.parse::<Ident>().is_ok_and(|ident| ident == "compare") && fork.peek(Token![=]);
+        let compare = if has_compare {
+            input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+            let compare = input.parse::<Expr>()?;
+            input.parse::<Token![,]>()?;
+            Some(compare)
+        } else {
+            None
+        };
+
+        let branches = Punctuated::<Expr, Token![,]>::parse_terminated(input)?
+            .into_iter()
+            .collect::<Vec<_>>();
+        if branches.is_empty() {
+            return Err(input.error("vote! needs at least one branch"));
+        }
+
+        Ok(VoteInput { cancel, compare, branches })
+    }
+}
+
+/// Gives every branch the same type by nesting them in `futures::future::Either`, so
+/// branches with different future types can go into one collection.
+pub(crate) fn same_type_futures(branches: &[Expr]) -> Vec<proc_macro2::TokenStream> {
+    let last = branches.len() - 1;
+    branches
+        .iter()
+        .enumerate()
+        .map(|(i, expr)| {
+            let mut future = if i == last {
+                quote! { #expr }
+            } else {
+                quote! { futures::future::Either::Left(#expr) }
+            };
+            for _ in 0..i {
+                future = quote! { futures::future::Either::Right(#future) };
+            }
+            future
+        })
+        .collect()
+}
+
+/// Waits until `n` of the branches have succeeded and returns their values as a `Vec`,
+/// dropping the rest. Returns a `MultiError` once `n` successes are no longer possible.
+/// `n` must be between 1 and the number of branches.
+/// With `within`, the result is a `TimeoutResult` instead.
+pub(crate) fn quorum(input: TokenStream) -> TokenStream {
+    let QuorumInput { cancel, required, branches, within } = parse_macro_input!(input as QuorumInput);
+
+    let futures = same_type_futures(&branches);
+    let reached = quote! {
+        parallel_macro_core::quorum(#required, [#(#futures),*])
+    };
+
+    let body = match within {
+        Some(limit) => quote! {
+            match tokio::time::timeout(#limit, #reached).await {
+                Ok(Ok(values)) => parallel_macro_core::TimeoutResult::Success(values),
+                Ok(Err(errors)) => parallel_macro_core::TimeoutResult::Error(errors),
+                Err(_) => parallel_macro_core::TimeoutResult::TimedOut,
+            }
+        },
+        None => quote! { #reached.await },
+    };
+    let expanded = with_cancel(&cancel, body);
+
+    TokenStream::from(expanded)
+}
+
+/// Runs every branch and returns the output that more than half of them agree on, as an
+/// `Option<Majority<T>>`. Outputs are compared with `==` unless a `compare` closure is given.
+/// Stops as soon as the majority is certain (or impossible), dropping the other branches.
+pub(crate) fn vote(input: TokenStream) -> TokenStream {
+    let VoteInput { cancel, compare, branches } = parse_macro_input!(input as VoteInput);
+
+    let futures = same_type_futures(&branches);
+    let body = match compare {
+        Some(compare) => quote! {
+            parallel_macro_core::vote_by([#(#futures),*], #compare).await
+        },
+        None => quote! {
+            parallel_macro_core::vote([#(#futures),*]).await
+        },
+    };
+    let expanded = with_cancel(&cancel, body);
+
+    TokenStream::from(expanded)
+}
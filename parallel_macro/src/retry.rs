@@ -0,0 +1,213 @@
+extern crate proc_macro;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{braced, parse::Parse, parse::ParseStream, parse_macro_input, token, Expr, Ident, Result, Token};
+
+use crate::cancel::{parse_cancel, with_cancel};
+use crate::duration::duration;
+
+// retry!(attempts = 3, backoff = exponential(100ms, jitter), per_attempt = 1s, deadline = 5s { call() })
+#[derive(Default)]
+struct RetryInput {
+    cancel: Option<Expr>,
+    attempts: Option<Expr>,
+    backoff: Option<Expr>,
+    per_attempt: Option<Expr>,
+    deadline: Option<Expr>,
+    retry_if: Option<Expr>,
+    body: Option<Expr>,
+}
+
+impl Parse for RetryInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut retry = RetryInput {
+            cancel: parse_cancel(input)?,
+            ..RetryInput::default()
+        };
+
+        // Parse `key = value` options up to the body
+        while !input.peek(token::Brace) {
+            let key = input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+            let value = Expr::parse_without_eager_brace(input)?;
+
+            let slot = match key.to_string().as_str() {
+                "attempts" => &mut retry.attempts,
+                "backoff" => &mut retry.backoff,
+                "per_attempt" => &mut retry.per_attempt,
+                "deadline" => &mut retry.deadline,
+                "retry_if" => &mut retry.retry_if,
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "unknown retry! option, expected `attempts`, `backoff`, `per_attempt`, `deadline` or `retry_if`",
+                    ))
+                },
+            };
+            if slot.replace(value).is_some() {
+                return Err(syn::Error::new(key.span(), format!("`{}` is set twice", key)));
+            }
+
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        if let Some(Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(lit), .. })) = &retry.attempts {
+            if lit.base10_parse::<u32>()? == 0 {
+                return Err(syn::Error::new(lit.span(), "retry! needs at least 1 attempt"));
+            }
+        }
+
+        let content;
+        braced!(content in input);
+        retry.body = Some(content.parse()?);
+
+        Ok(retry)
+    }
+}
+
+/// A `parallel_macro_core::Backoff` for `none`, `fixed(delay)` or `exponential(base[, jitter])`;
+/// any other expression is used as a `Backoff` value as is
+fn backoff(expr: &Expr) -> Result<proc_macro2::TokenStream> {
+    let is_ident = |expr: &Expr, name: &str| matches!(expr, Expr::Path(path) if path.path.is_ident(name));
+
+    if is_ident(expr, "none") {
+        return Ok(quote! { parallel_macro_core::Backoff::None });
+    }
+
+    if let Expr::Call(call) = expr {
+        let args = call.args.iter().collect::<Vec<_>>();
+        if is_ident(&call.func, "fixed") {
+            let [delay] = args.as_slice() else {
+                return Err(syn::Error::new_spanned(call, "expected `fixed(<delay>)`"));
+            };
+            let delay = duration(delay)?;
+            return Ok(quote! { parallel_macro_core::Backoff::Fixed(#delay) });
+        }
+        if is_ident(&call.func, "exponential") {
+            let (base, jitter) = match args.as_slice() {
+                [base] => (base, false),
+                [base, jitter] if is_ident(jitter, "jitter") => (base, true),
+                _ => return Err(syn::Error::new_spanned(call, "expected `exponential(<base>)` or `exponential(<base>, jitter)`")),
+            };
+            let base = duration(base)?;
+            return Ok(quote! { parallel_macro_core::Backoff::Exponential { base: #base, jitter: #jitter } });
+        }
+    }
+
+    Ok(quote! { #expr })
+}
+
+/// Runs the body until it succeeds, up to `attempts` times (3 by default), waiting
+/// according to `backoff` between attempts. `per_attempt` limits each attempt and
+/// `deadline` all of them together; `retry_if` gets a reference to each error and decides
+/// whether it is worth another attempt (timed out attempts are always retried, panicked
+/// ones never are).
+/// Returns a `RetryResult` with the last outcome and the number of attempts. When the
+/// deadline runs out, the outcome is that of the last finished attempt (`TimedOut` if
+/// none finished).
+pub(crate) fn retry(input: TokenStream) -> TokenStream {
+    let RetryInput { cancel, attempts, backoff: backoff_expr, per_attempt, deadline, retry_if, body } =
+        parse_macro_input!(input as RetryInput);
+
+    let durations = (|| {
+        let backoff = backoff_expr.as_ref().map(backoff).transpose()?;
+        let per_attempt = per_attempt.as_ref().map(duration).transpose()?;
+        let deadline = deadline.as_ref().map(duration).transpose()?;
+        Ok::<_, syn::Error>((backoff, per_attempt, deadline))
+    })();
+    let (backoff, per_attempt, deadline) = match durations {
+        Ok(durations) => durations,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let attempts = attempts.map_or_else(|| quote! { 3 }, |attempts| quote! { #attempts });
+    let backoff = backoff.unwrap_or_else(|| quote! { parallel_macro_core::Backoff::None });
+    let retry_if = retry_if.map_or_else(|| quote! { |_| true }, |retry_if| quote! { #retry_if });
+
+    // Same shape as timeout_with_result!: an attempt that runs out of time is TimedOut
+    // and one that panics is Panicked
+    let attempt = match per_attempt {
+        Some(limit) => quote! {
+            match tokio::time::timeout(#limit, __attempt_future).await {
+                Ok(Ok(Ok(val))) => parallel_macro_core::TimeoutResult::Success(val),
+                Ok(Ok(Err(e))) => parallel_macro_core::TimeoutResult::Error(e),
+                Ok(Err(payload)) => parallel_macro_core::TimeoutResult::Panicked(
+                    parallel_macro_core::PanicInfo::new(payload, std::panic::Location::caller())
+                ),
+                Err(_) => parallel_macro_core::TimeoutResult::TimedOut,
+            }
+        },
+        None => quote! {
+            match __attempt_future.await {
+                Ok(Ok(val)) => parallel_macro_core::TimeoutResult::Success(val),
+                Ok(Err(e)) => parallel_macro_core::TimeoutResult::Error(e),
+                Err(payload) => parallel_macro_core::TimeoutResult::Panicked(
+                    parallel_macro_core::PanicInfo::new(payload, std::panic::Location::caller())
+                ),
+            }
+        },
+    };
+
+    let attempts_loop = quote! {
+        async {
+            loop {
+                *__attempts_made += 1;
+                let __attempt_future = #body;
+                let __attempt_future = futures::FutureExt::catch_unwind(std::panic::AssertUnwindSafe(__attempt_future));
+                let outcome = #attempt;
+
+                let retryable = match &outcome {
+                    parallel_macro_core::TimeoutResult::Success(_) => false,
+                    parallel_macro_core::TimeoutResult::Error(err) => __retry_if(err),
+                    parallel_macro_core::TimeoutResult::TimedOut => true,
+                    parallel_macro_core::TimeoutResult::Panicked(_) => false,
+                };
+                if !retryable || *__attempts_made >= __max_attempts {
+                    break outcome;
+                }
+
+                *__last_outcome = Some(outcome);
+                tokio::time::sleep(__backoff.delay(*__attempts_made)).await;
+            }
+        }
+    };
+
+    // The count and the last outcome live outside the attempts so they survive the
+    // deadline dropping them
+    let run = match deadline {
+        Some(deadline) => quote! {
+            let finished = tokio::time::timeout(#deadline, #attempts_loop).await;
+            let deadline_exceeded = finished.is_err();
+            let outcome = finished.unwrap_or_else(|_| {
+                __last.take().unwrap_or(parallel_macro_core::TimeoutResult::TimedOut)
+            });
+        },
+        None => quote! {
+            let outcome = #attempts_loop.await;
+            let deadline_exceeded = false;
+        },
+    };
+
+    // Prefixed so they don't shadow variables used in the body or `retry_if`
+    let retried = quote! {
+        {
+            let __max_attempts: u32 = #attempts;
+            assert!(__max_attempts > 0, "retry! needs at least 1 attempt");
+            let __backoff: parallel_macro_core::Backoff = #backoff;
+            let __retry_if: &dyn Fn(&_) -> bool = &(#retry_if);
+            let mut __attempts = 0u32;
+            let __attempts_made = &mut __attempts;
+            let mut __last = None;
+            let __last_outcome = &mut __last;
+
+            #run
+
+            parallel_macro_core::RetryResult { outcome, attempts: __attempts, deadline_exceeded }
+        }
+    };
+    let expanded = with_cancel(&cancel, retried);
+
+    TokenStream::from(expanded)
+}
@@ -1,24 +1,107 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
 use quote::{quote};
-use syn::{parse_macro_input, Expr, Token, parse::{Parse, ParseStream}, Result};
+use syn::{parse_macro_input, Expr, Ident, Token, parse::{Parse, ParseStream}, Result};
+
+use crate::cancel::{parse_cancel, with_cancel};
+use crate::duration::parse_duration;
 
 enum TimeoutFallback {
     None,
     Else(Expr),
 }
 
+/// Options that may come before the duration of a timeout macro, in any order:
+/// `blocking,`, `resume_unwind,`, `cancel = <token>,` and `label = "<name>",`
+#[derive(Default)]
+pub(crate) struct TimeoutOptions {
+    // Block the calling thread until done instead of expanding to a future
+    pub(crate) blocking: bool,
+    // Re-raise a panic of the body on the caller's task instead of reporting it
+    pub(crate) resume_unwind: Option<Ident>,
+    pub(crate) cancel: Option<Expr>,
+    // Name reported in `TimeoutError::Elapsed`
+    pub(crate) label: Option<Expr>,
+}
+
+impl TimeoutOptions {
+    /// Rejects a `label` in calls that never return a `TimeoutError`
+    pub(crate) fn check_label_unused(&self, reason: &str) -> Result<()> {
+        match &self.label {
+            Some(label) => Err(syn::Error::new_spanned(label, format!("`label` is not used {}", reason))),
+            None => Ok(()),
+        }
+    }
+
+    /// Rejects `resume_unwind` in calls where a panic of the body already unwinds the caller
+    pub(crate) fn check_resume_unwind_unused(&self, reason: &str) -> Result<()> {
+        match &self.resume_unwind {
+            Some(key) => Err(syn::Error::new(key.span(), format!("`resume_unwind` is not used {}", reason))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Parse for TimeoutOptions {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut options = TimeoutOptions::default();
+
+        loop {
+            let span = input.span();
+            if let Some(cancel) = parse_cancel(input)? {
+                if options.cancel.replace(cancel).is_some() {
+                    return Err(syn::Error::new(span, "`cancel` is set twice"));
+                }
+                continue;
+            }
+
+            let fork = input.fork();
+            let key = fork.parse::<Ident>().ok();
+            if key.as_ref().is_some_and(|key| key == "label") && fork.peek(Token![=]) && !fork.peek(Token![==]) {
+                input.parse::<Ident>()?;
+                input.parse::<Token![=]>()?;
+                let label = input.parse::<Expr>()?;
+                input.parse::<Token![,]>()?;
+                if options.label.replace(label).is_some() {
+                    return Err(syn::Error::new(span, "`label` is set twice"));
+                }
+                continue;
+            }
+            if key.as_ref().is_some_and(|key| key == "blocking") && fork.peek(Token![,]) {
+                input.parse::<Ident>()?;
+                input.parse::<Token![,]>()?;
+                options.blocking = true;
+                continue;
+            }
+            if key.as_ref().is_some_and(|key| key == "resume_unwind") && fork.peek(Token![,]) {
+                let key = input.parse::<Ident>()?;
+                input.parse::<Token![,]>()?;
+                if options.resume_unwind.replace(key).is_some() {
+                    return Err(syn::Error::new(span, "`resume_unwind` is set twice"));
+                }
+                continue;
+            }
+
+            return Ok(options);
+        }
+    }
+}
+
 // Input struct for the standard timeout macro (optional fallback)
 struct TimeoutInput {
-    duration: Expr,
+    options: TimeoutOptions,
+    duration: proc_macro2::TokenStream,
     body: Expr,
     fallback: TimeoutFallback,
 }
 
 impl Parse for TimeoutInput {
     fn parse(input: ParseStream) -> Result<Self> {
+        // Parse optional `blocking` and cancellation token
+        let options = input.parse()?;
+        
         // Parse duration
-        let duration = input.parse()?;
+        let duration = parse_duration(input)?;
         
         // Parse body
         let body = input.parse()?;
@@ -32,6 +115,7 @@ impl Parse for TimeoutInput {
         };
         
         Ok(TimeoutInput {
+            options,
             duration,
             body,
             fallback,
@@ -41,15 +125,19 @@ impl Parse for TimeoutInput {
 
 // Input struct for timeout_fallback macro (required fallback)
 struct TimeoutFallbackInput {
-    duration: Expr,
+    options: TimeoutOptions,
+    duration: proc_macro2::TokenStream,
     body: Expr,
     fallback: Expr,
 }
 
 impl Parse for TimeoutFallbackInput {
     fn parse(input: ParseStream) -> Result<Self> {
+        // Parse optional `blocking` and cancellation token
+        let options = input.parse()?;
+        
         // Parse duration
-        let duration = input.parse()?;
+        let duration = parse_duration(input)?;
         
         // Parse body
         let body = input.parse()?;
@@ -59,6 +147,7 @@ impl Parse for TimeoutFallbackInput {
         let fallback = input.parse()?;
         
         Ok(TimeoutFallbackInput {
+            options,
             duration,
             body,
             fallback,
@@ -66,281 +155,202 @@ impl Parse for TimeoutFallbackInput {
     }
 }
 
+/// Expands the statements in `async_body` to a future, or with `blocking` to an expression
+/// that runs them to completion and evaluates to their result.
+pub(crate) fn run_timeout(blocking: bool, async_body: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    if blocking {
+        let run = block_on_runtime(async_body);
+        quote! {
+            {
+                #run
+            }
+        }
+    } else {
+        quote! {
+            async {
+                #async_body
+            }
+        }
+    }
+}
+
+/// Runs the statements in `async_body` to completion and evaluates to their result.
+/// Blocks on the current runtime if there is one, otherwise on the shared runtime.
+pub(crate) fn block_on_runtime(async_body: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        parallel_macro_core::block_on(async {
+            #async_body
+        })
+    }
+}
+
+/// `Err(TimeoutError::Elapsed)` for a call that started at `__started` and ran out of `__limit`
+fn elapsed_error(label: &Option<Expr>) -> proc_macro2::TokenStream {
+    let label = match label {
+        Some(label) => quote! { Some(#label) },
+        None => quote! { None },
+    };
+    quote! {
+        Err(parallel_macro_core::TimeoutError::Elapsed {
+            limit: __limit,
+            elapsed: __started.elapsed(),
+            label: #label,
+        })
+    }
+}
+
 /// Original timeout macro that returns a Result
 pub(crate) fn timeout(input: TokenStream) -> TokenStream {
-    let TimeoutInput { duration, body, fallback } = parse_macro_input!(input as TimeoutInput);
-    
-    let expanded = match fallback {
-        TimeoutFallback::None => {
-            // Return Result for basic timeout usage
-            quote! {
-                {
-                    use tokio::time::timeout;
-                    use std::time::Duration;
-                    
-                    // Convert the numeric duration to seconds
-                    let duration_secs = Duration::from_secs(#duration as u64);
-                    
-                    // Check if we're inside a runtime or need to create one
-                    if let Ok(handle) = tokio::runtime::Handle::try_current() {
-                        // We're in a runtime, use the current handle to enter it
-                        let _guard = handle.enter();
-                        
-                        // Apply timeout to the future and execute it "immediately" using spawn_blocking
-                        tokio::task::block_in_place(|| {
-                            handle.block_on(async {
-                                let body_future = #body;
-                                let timeout_future = timeout(duration_secs, body_future);
-                                
-                                match timeout_future.await {
-                                    Ok(result) => Ok(result),
-                                    Err(_) => Err(format!("Operation timed out after {} seconds", #duration)),
-                                }
-                            })
-                        })
-                    } else {
-                        // Not in a runtime, create a new one
-                        tokio::runtime::Runtime::new()
-                            .unwrap()
-                            .block_on(async {
-                                let body_future = #body;
-                                let timeout_future = timeout(duration_secs, body_future);
-                                
-                                match timeout_future.await {
-                                    Ok(result) => Ok(result),
-                                    Err(_) => Err(format!("Operation timed out after {} seconds", #duration)),
-                                }
-                            })
-                    }
-                }
-            }
-        },
+    let TimeoutInput { options, duration, body, fallback } = parse_macro_input!(input as TimeoutInput);
+    if let Err(err) = options.check_resume_unwind_unused("by timeout!, its body already runs on the caller's task") {
+        return err.to_compile_error().into();
+    }
+
+    let (start_clock, on_timeout) = match fallback {
+        // Return a TimeoutError for basic timeout usage
+        TimeoutFallback::None => (
+            quote! { let __started = tokio::time::Instant::now(); },
+            elapsed_error(&options.label),
+        ),
+        // Use custom fallback on timeout, but wrap in Result
         TimeoutFallback::Else(fallback_expr) => {
-            // Use custom fallback on timeout, but wrap in Result
-            quote! {
-                {
-                    use tokio::time::timeout;
-                    use std::time::Duration;
-                    
-                    // Convert the numeric duration to seconds
-                    let duration_secs = Duration::from_secs(#duration as u64);
-                    
-                    // Check if we're inside a runtime or need to create one
-                    if let Ok(handle) = tokio::runtime::Handle::try_current() {
-                        // We're in a runtime, use the current handle to enter it
-                        let _guard = handle.enter();
-                        
-                        // Apply timeout to the future and execute it "immediately" using spawn_blocking
-                        tokio::task::block_in_place(|| {
-                            handle.block_on(async {
-                                let body_future = #body;
-                                let timeout_future = timeout(duration_secs, body_future);
-                                
-                                match timeout_future.await {
-                                    Ok(result) => Ok(result),
-                                    Err(_) => Err({
-                                        #fallback_expr
-                                    }),
-                                }
-                            })
-                        })
-                    } else {
-                        // Not in a runtime, create a new one
-                        tokio::runtime::Runtime::new()
-                            .unwrap()
-                            .block_on(async {
-                                let body_future = #body;
-                                let timeout_future = timeout(duration_secs, body_future);
-                                
-                                match timeout_future.await {
-                                    Ok(result) => Ok(result),
-                                    Err(_) => Err({
-                                        #fallback_expr
-                                    }),
-                                }
-                            })
-                    }
-                }
+            if let Err(err) = options.check_label_unused("with an `else` clause") {
+                return err.to_compile_error().into();
             }
-        }
+            let on_timeout = quote! {
+                Err({
+                    #fallback_expr
+                })
+            };
+            (quote! {}, on_timeout)
+        },
     };
-    
+
+    let timeout_body = with_cancel(&options.cancel, quote! {
+        #start_clock
+        let __body_future = #body;
+        let __timeout_future = timeout(__limit, __body_future);
+
+        match __timeout_future.await {
+            Ok(result) => Ok(result),
+            Err(_) => #on_timeout,
+        }
+    });
+
+    let expanded = run_timeout(options.blocking, quote! {
+        use tokio::time::timeout;
+        use std::time::Duration;
+
+        let __limit: Duration = #duration;
+
+        #timeout_body
+    });
+
     TokenStream::from(expanded)
 }
 
+/// Body of an async block that awaits `body` for at most `duration` and evaluates
+/// `fallback` if it runs out of time. Also used for parallel!'s per-branch timeouts.
+pub(crate) fn timeout_fallback_body(duration: &proc_macro2::TokenStream, body: &Expr, fallback: &Expr) -> proc_macro2::TokenStream {
+    quote! {
+        use tokio::time::timeout;
+        use std::time::Duration;
+
+        let __limit: Duration = #duration;
+
+        let __body_future = #body;
+        let __timeout_future = timeout(__limit, __body_future);
+
+        match __timeout_future.await {
+            Ok(result) => result,
+            Err(_) => {
+                #fallback
+            }
+        }
+    }
+}
+
 /// New timeout_fallback macro that directly returns the fallback value
-/// This always requires an else clause and does not need to be awaited
+/// This always requires an else clause
 pub(crate) fn timeout_fallback(input: TokenStream) -> TokenStream {
-    let TimeoutFallbackInput { duration, body, fallback } = parse_macro_input!(input as TimeoutFallbackInput);
-    
+    let TimeoutFallbackInput { options, duration, body, fallback } = parse_macro_input!(input as TimeoutFallbackInput);
+    if let Err(err) = options.check_label_unused("by timeout_fallback!") {
+        return err.to_compile_error().into();
+    }
+    if let Err(err) = options.check_resume_unwind_unused("by timeout_fallback!, its body already runs on the caller's task") {
+        return err.to_compile_error().into();
+    }
+
     // Use custom fallback on timeout - direct return, no Result wrapping
-    let expanded = quote! {
-        {
-            use tokio::time::timeout;
-            use std::time::Duration;
-            
-            // Convert the numeric duration to seconds
-            let duration_secs = Duration::from_secs(#duration as u64);
-            
-            // Check if we're inside a runtime or need to create one
-            if let Ok(handle) = tokio::runtime::Handle::try_current() {
-                // We're in a runtime, use the current handle to enter it
-                let _guard = handle.enter();
-                
-                // Apply timeout to the future and execute it "immediately" using spawn_blocking
-                tokio::task::block_in_place(|| {
-                    handle.block_on(async {
-                        let body_future = #body;
-                        let timeout_future = timeout(duration_secs, body_future);
-                        
-                        match timeout_future.await {
-                            Ok(result) => result,
-                            Err(_) => {
-                                #fallback
-                            }
-                        }
-                    })
-                })
-            } else {
-                // Not in a runtime, create a new one
-                tokio::runtime::Runtime::new()
-                    .unwrap()
-                    .block_on(async {
-                        let body_future = #body;
-                        let timeout_future = timeout(duration_secs, body_future);
-                        
-                        match timeout_future.await {
-                            Ok(result) => result,
-                            Err(_) => {
-                                #fallback
-                            }
-                        }
-                    })
-            }
-        }
-    };
-    
+    let timeout_body = with_cancel(&options.cancel, timeout_fallback_body(&duration, &body, &fallback));
+    let expanded = run_timeout(options.blocking, timeout_body);
+
     TokenStream::from(expanded)
 }
 
 
 pub(crate) fn timeout_value(input: TokenStream) -> TokenStream {
-    let TimeoutInput { duration, body, fallback } = parse_macro_input!(input as TimeoutInput);
-    
-    let expanded = match fallback {
-        TimeoutFallback::None => {
-            // Return Result for basic timeout usage
+    let TimeoutInput { options, duration, body, fallback } = parse_macro_input!(input as TimeoutInput);
+
+    let (start_clock, on_panic, on_cancelled, on_timeout) = match fallback {
+        // Return a TimeoutError for basic timeout usage
+        TimeoutFallback::None => (
+            quote! { let __started = tokio::time::Instant::now(); },
             quote! {
-                {
-                    use tokio::time::timeout;
-                    use std::time::Duration;
-                    
-                    // Convert the numeric duration to seconds
-                    let duration_secs = Duration::from_secs(#duration as u64);
-                    
-                    // Check if we're inside a runtime or need to create one
-                    if let Ok(handle) = tokio::runtime::Handle::try_current() {
-                        // We're in a runtime, use the current handle to enter it
-                        let _guard = handle.enter();
-                        
-                        // Wrap the body expression in a task and apply timeout
-                        tokio::task::block_in_place(|| {
-                            handle.block_on(async {
-                                // Create a task that performs the evaluation
-                                let task = tokio::task::spawn(async move {
-                                    #body
-                                });
-                                
-                                // Apply timeout to the task
-                                match timeout(duration_secs, task).await {
-                                    Ok(Ok(value)) => Ok(value),
-                                    Ok(Err(_)) => Err(format!("Task panicked")),
-                                    Err(_) => Err(format!("Operation timed out after {} seconds", #duration)),
-                                }
-                            })
-                        })
-                    } else {
-                        // Not in a runtime, create a new one
-                        tokio::runtime::Runtime::new()
-                            .unwrap()
-                            .block_on(async {
-                                // Create a task that performs the evaluation
-                                let task = tokio::task::spawn(async move {
-                                    #body
-                                });
-                                
-                                // Apply timeout to the task
-                                match timeout(duration_secs, task).await {
-                                    Ok(Ok(value)) => Ok(value),
-                                    Ok(Err(_)) => Err(format!("Task panicked")),
-                                    Err(_) => Err(format!("Operation timed out after {} seconds", #duration)),
-                                }
-                            })
-                    }
-                }
-            }
-        },
+                Err(parallel_macro_core::TimeoutError::Panicked {
+                    info: parallel_macro_core::PanicInfo::new(payload, std::panic::Location::caller()),
+                })
+            },
+            quote! { Err(parallel_macro_core::TimeoutError::Cancelled) },
+            elapsed_error(&options.label),
+        ),
+        // Use custom fallback on timeout, but wrap in Result. A panic can't be reported in
+        // the fallback's type and isn't a timeout, so it is re-raised on the caller's task.
         TimeoutFallback::Else(fallback_expr) => {
-            // Use custom fallback on timeout, but wrap in Result
-            quote! {
-                {
-                    use tokio::time::timeout;
-                    use std::time::Duration;
-                    
-                    // Convert the numeric duration to seconds
-                    let duration_secs = Duration::from_secs(#duration as u64);
-                    
-                    // Check if we're inside a runtime or need to create one
-                    if let Ok(handle) = tokio::runtime::Handle::try_current() {
-                        // We're in a runtime, use the current handle to enter it
-                        let _guard = handle.enter();
-                        
-                        // Wrap the body expression in a task and apply timeout
-                        tokio::task::block_in_place(|| {
-                            handle.block_on(async {
-                                // Create a task that performs the evaluation
-                                let task = tokio::task::spawn(async move {
-                                    #body
-                                });
-                                
-                                // Apply timeout to the task
-                                match timeout(duration_secs, task).await {
-                                    Ok(Ok(value)) => Ok(value),
-                                    Ok(Err(_)) => Err({
-                                        #fallback_expr
-                                    }),
-                                    Err(_) => Err({
-                                        #fallback_expr
-                                    }),
-                                }
-                            })
-                        })
-                    } else {
-                        // Not in a runtime, create a new one
-                        tokio::runtime::Runtime::new()
-                            .unwrap()
-                            .block_on(async {
-                                // Create a task that performs the evaluation
-                                let task = tokio::task::spawn(async move {
-                                    #body
-                                });
-                                
-                                // Apply timeout to the task
-                                match timeout(duration_secs, task).await {
-                                    Ok(Ok(value)) => Ok(value),
-                                    Ok(Err(_)) => Err({
-                                        #fallback_expr
-                                    }),
-                                    Err(_) => Err({
-                                        #fallback_expr
-                                    }),
-                                }
-                            })
-                    }
-                }
+            if let Err(err) = options.check_label_unused("with an `else` clause") {
+                return err.to_compile_error().into();
             }
-        }
+            (
+                quote! {},
+                quote! { std::panic::resume_unwind(payload) },
+                quote! { Err({ #fallback_expr }) },
+                quote! { Err({ #fallback_expr }) },
+            )
+        },
+    };
+    // With `resume_unwind` a panic is re-raised here rather than reported as a TimeoutError
+    let on_panic = if options.resume_unwind.is_some() {
+        quote! { std::panic::resume_unwind(payload) }
+    } else {
+        on_panic
     };
-    
+
+    let timeout_body = with_cancel(&options.cancel, quote! {
+        #start_clock
+
+        // Create a task that performs the evaluation. Timing out or dropping the call
+        // (e.g. when it is cancelled) aborts the task. `Location::caller()` in the generated
+        // code is the macro call, reported as where the task was spawned if it panics.
+        let __task = parallel_macro_core::spawn_branch(0, async move { #body });
+
+        // Apply timeout to the task
+        match timeout(__limit, __task).await {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(parallel_macro_core::SpawnError::Panicked { payload, .. })) => #on_panic,
+            // Only happens while the runtime shuts down
+            Ok(Err(parallel_macro_core::SpawnError::Cancelled { .. })) => #on_cancelled,
+            Err(_) => #on_timeout,
+        }
+    });
+
+    let expanded = run_timeout(options.blocking, quote! {
+        use tokio::time::timeout;
+        use std::time::Duration;
+
+        let __limit: Duration = #duration;
+
+        #timeout_body
+    });
+
     TokenStream::from(expanded)
 }
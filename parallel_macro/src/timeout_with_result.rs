@@ -3,6 +3,10 @@ use proc_macro::TokenStream;
 use quote::{quote};
 use syn::{parse_macro_input, Expr, Token, parse::{Parse, ParseStream}, Result};
 
+use crate::cancel::with_cancel;
+use crate::duration::parse_duration;
+use crate::timeout::{run_timeout, TimeoutOptions};
+
 enum TimeoutFallback {
     None,
     Else(Expr),
@@ -13,15 +17,19 @@ enum TimeoutFallback {
 
 // Input struct for the standard timeout macro (optional fallback)
 struct TimeoutInput {
-    duration: Expr,
+    options: TimeoutOptions,
+    duration: proc_macro2::TokenStream,
     body: Expr,
     fallback: TimeoutFallback,
 }
 
 impl Parse for TimeoutInput {
     fn parse(input: ParseStream) -> Result<Self> {
+        // Parse optional `blocking` and cancellation token
+        let options = input.parse()?;
+        
         // Parse duration
-        let duration = input.parse()?;
+        let duration = parse_duration(input)?;
         
         // Parse body
         let body = input.parse()?;
@@ -35,6 +43,7 @@ impl Parse for TimeoutInput {
         };
         
         Ok(TimeoutInput {
+            options,
             duration,
             body,
             fallback,
@@ -71,125 +80,60 @@ impl Parse for TimeoutFallbackInput {
 
 /// Original timeout macro that returns a Result
 pub(crate) fn timeout_with_result(input: TokenStream) -> TokenStream {
-    let TimeoutInput { duration, body, fallback } = parse_macro_input!(input as TimeoutInput);
+    let TimeoutInput { options, duration, body, fallback } = parse_macro_input!(input as TimeoutInput);
+    if let Err(err) = options.check_label_unused("by timeout_with_result!") {
+        return err.to_compile_error().into();
+    }
     
-    let expanded = match fallback {
-        TimeoutFallback::None => {
-            // Return Result for basic timeout usage
-            quote! {
-                {
-                    use tokio::time::timeout;
-                    use std::time::Duration;
-                    
-                    // Convert the numeric duration to seconds
-                    let duration_secs = Duration::from_secs(#duration as u64);
-                    
-                    // Check if we're inside a runtime or need to create one
-                    if let Ok(handle) = tokio::runtime::Handle::try_current() {
-                        // We're in a runtime, use the current handle to enter it
-                        let _guard = handle.enter();
-                        
-                        // Apply timeout to the future and execute it "immediately" using spawn_blocking
-                        tokio::task::block_in_place(|| {
-                            handle.block_on(async {
-                                let body_future = #body;
-                                let timeout_future = timeout(duration_secs, body_future);
-                                
-                                match timeout_future.await {
-                                    Ok(result) => match result {
-                                        Ok(val) => TimeoutResult::Success(val),
-                                        Err(e) => TimeoutResult::Error(e),
-                                    },
-                                    Err(_) => TimeoutResult::TimedOut,
-                                }
-                            })
-                        })
-                    } else {
-                        // Not in a runtime, create a new one
-                        tokio::runtime::Runtime::new()
-                            .unwrap()
-                            .block_on(async {
-                                let body_future = #body;
-                                let timeout_future = timeout(duration_secs, body_future);
-                                
-                                match timeout_future.await {
-                                    Ok(result) => match result {
-                                        Ok(val) => TimeoutResult::Success(val),
-                                        Err(e) => TimeoutResult::Error(e),
-                                    },
-                                    Err(_) => TimeoutResult::TimedOut,
-                                }
-                            })
-                    }
-                }
-            }
+    let on_timeout = match fallback {
+        // Return Result for basic timeout usage
+        TimeoutFallback::None => quote! {
+            TimeoutResult::TimedOut
         },
-        TimeoutFallback::Else(fallback_expr) => {
-            // Use custom fallback on timeout, but wrap in Result
-            quote! {
-                {
-                    use tokio::time::timeout;
-                    use std::time::Duration;
-                    use parallel_macro_core::TimeoutResult;
-                    
-                    // Convert the numeric duration to seconds
-                    let duration_secs = Duration::from_secs(#duration as u64);
-                    
-                    // Check if we're inside a runtime or need to create one
-                    if let Ok(handle) = tokio::runtime::Handle::try_current() {
-                        // We're in a runtime, use the current handle to enter it
-                        let _guard = handle.enter();
-                        
-                        // Apply timeout to the future and execute it "immediately" using spawn_blocking
-                        tokio::task::block_in_place(|| {
-                            handle.block_on(async {
-                                let body_future = #body;
-                                let timeout_future = timeout(duration_secs, body_future);
-                                
-                                match timeout_future.await {
-                                    Ok(result) => match result {
-                                        Ok(val) => TimeoutResult::Success(val),
-                                        Err(e) => TimeoutResult::Error(e),
-                                    },
-                                    Err(_) => {
-                                        let fallback_result = #fallback_expr;
+        // Use custom fallback on timeout, but wrap in Result
+        TimeoutFallback::Else(fallback_expr) => quote! {
+            {
+                let fallback_result = #fallback_expr;
 
-                                        match fallback_result {
-                                            Ok(result) => TimeoutResult::Success(result),
-                                            Err(err) => TimeoutResult::Error(err),
-                                        }
-                                    },
-                                }
-                            })
-                        })
-                    } else {
-                        // Not in a runtime, create a new one
-                        tokio::runtime::Runtime::new()
-                            .unwrap()
-                            .block_on(async {
-                                let body_future = #body;
-                                let timeout_future = timeout(duration_secs, body_future);
-                                
-                                match timeout_future.await {
-                                    Ok(result) => match result {
-                                        Ok(val) => TimeoutResult::Success(val),
-                                        Err(e) => TimeoutResult::Error(e),
-                                    },
-                                    Err(_) => {
-                                        let fallback_result = #fallback_expr;
-
-                                        match fallback_result {
-                                            Ok(result) => TimeoutResult::Success(result),
-                                            Err(err) => TimeoutResult::Error(err),
-                                        }
-                                    },
-                                }
-                            })
-                    }
+                match fallback_result {
+                    Ok(result) => TimeoutResult::Success(result),
+                    Err(err) => TimeoutResult::Error(err),
                 }
             }
-        }
+        },
+    };
+    
+    let on_panic = if options.resume_unwind.is_some() {
+        quote! { std::panic::resume_unwind(payload) }
+    } else {
+        quote! { TimeoutResult::Panicked(parallel_macro_core::PanicInfo::new(payload, std::panic::Location::caller())) }
     };
     
+    let timeout_body = with_cancel(&options.cancel, quote! {
+        // Catch a panic of the body so it isn't mistaken for a timeout or an error
+        let __body_future = #body;
+        let __body_future = futures::FutureExt::catch_unwind(std::panic::AssertUnwindSafe(__body_future));
+        let __timeout_future = timeout(__limit, __body_future);
+        
+        match __timeout_future.await {
+            Ok(Ok(result)) => match result {
+                Ok(val) => TimeoutResult::Success(val),
+                Err(e) => TimeoutResult::Error(e),
+            },
+            Ok(Err(payload)) => #on_panic,
+            Err(_) => #on_timeout,
+        }
+    });
+    
+    let expanded = run_timeout(options.blocking, quote! {
+        use tokio::time::timeout;
+        use std::time::Duration;
+        use parallel_macro_core::TimeoutResult;
+        
+        let __limit: Duration = #duration;
+        
+        #timeout_body
+    });
+    
     TokenStream::from(expanded)
 }
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// State of a `CircuitBreaker`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls go through; failures are counted
+    Closed,
+    /// Calls fail fast until the cool-down has passed
+    Open,
+    /// The cool-down has passed and a single probe call is let through to test the service
+    HalfOpen,
+}
+
+/// Thresholds of a `CircuitBreaker`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitConfig {
+    /// Consecutive failures (errors or timeouts) that open the circuit; at least 1
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before letting a probe through
+    pub cool_down: Duration,
+}
+
+impl Default for CircuitConfig {
+    fn default() -> Self {
+        CircuitConfig {
+            failure_threshold: 5,
+            cool_down: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Circuit {
+    config: CircuitConfig,
+    state: CircuitState,
+    failures: u32,
+    opened_at: Option<Instant>,
+    probing: bool,
+}
+
+/// Stops calling a service that keeps failing: after `failure_threshold` consecutive
+/// failures the circuit opens and calls fail fast. Once `cool_down` has passed one probe
+/// call is let through; if it succeeds the circuit closes again, otherwise it reopens.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    circuit: Mutex<Circuit>,
+}
+
+static BREAKERS: OnceLock<Mutex<HashMap<String, Arc<CircuitBreaker>>>> = OnceLock::new();
+
+fn breakers() -> MutexGuard<'static, HashMap<String, Arc<CircuitBreaker>>> {
+    BREAKERS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+fn check_config(config: &CircuitConfig) {
+    assert!(config.failure_threshold > 0, "`failure_threshold` must be at least 1");
+}
+
+impl CircuitBreaker {
+    /// Panics if `config.failure_threshold` is 0
+    pub fn new(config: CircuitConfig) -> Self {
+        check_config(&config);
+        CircuitBreaker {
+            circuit: Mutex::new(Circuit {
+                config,
+                state: CircuitState::Closed,
+                failures: 0,
+                opened_at: None,
+                probing: false,
+            }),
+        }
+    }
+
+    /// The breaker registered under `name`, created with the default config on first use.
+    /// `circuit!("name" ...)` calls share it.
+    pub fn get(name: &str) -> Arc<CircuitBreaker> {
+        Arc::clone(
+            breakers()
+                .entry(name.to_string())
+                .or_insert_with(|| Arc::new(CircuitBreaker::new(CircuitConfig::default()))),
+        )
+    }
+
+    /// Sets the thresholds of the breaker registered under `name`, keeping its state.
+    /// Panics if `config.failure_threshold` is 0.
+    pub fn configure(name: &str, config: CircuitConfig) {
+        check_config(&config);
+        CircuitBreaker::get(name).lock().config = config;
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.lock().state
+    }
+
+    /// Closes the circuit and forgets past failures
+    pub fn reset(&self) {
+        let mut circuit = self.lock();
+        circuit.state = CircuitState::Closed;
+        circuit.failures = 0;
+        circuit.opened_at = None;
+        circuit.probing = false;
+    }
+
+    /// Asks to make a call. Returns `None` if the call should fail fast, otherwise a permit
+    /// to report the outcome of the call with.
+    pub fn try_acquire(&self) -> Option<CircuitPermit<'_>> {
+        let mut circuit = self.lock();
+        match circuit.state {
+            CircuitState::Closed => {},
+            CircuitState::Open => {
+                let cooled_down = circuit
+                    .opened_at
+                    .is_some_and(|opened_at| opened_at.elapsed() >= circuit.config.cool_down);
+                if !cooled_down {
+                    return None;
+                }
+                circuit.state = CircuitState::HalfOpen;
+                circuit.probing = true;
+            },
+            CircuitState::HalfOpen => {
+                if circuit.probing {
+                    return None;
+                }
+                circuit.probing = true;
+            },
+        }
+
+        Some(CircuitPermit { breaker: self, reported: false })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Circuit> {
+        self.circuit.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Permission to make one call through a `CircuitBreaker`.
+/// Dropping it without reporting (e.g. when the call is cancelled) counts as neither a
+/// success nor a failure.
+#[derive(Debug)]
+pub struct CircuitPermit<'a> {
+    breaker: &'a CircuitBreaker,
+    reported: bool,
+}
+
+impl CircuitPermit<'_> {
+    pub fn success(mut self) {
+        self.reported = true;
+        let mut circuit = self.breaker.lock();
+        circuit.state = CircuitState::Closed;
+        circuit.failures = 0;
+        circuit.opened_at = None;
+        circuit.probing = false;
+    }
+
+    pub fn failure(mut self) {
+        self.reported = true;
+        let mut circuit = self.breaker.lock();
+        circuit.failures += 1;
+        if circuit.state == CircuitState::HalfOpen || circuit.failures >= circuit.config.failure_threshold {
+            circuit.state = CircuitState::Open;
+            circuit.opened_at = Some(Instant::now());
+        }
+        circuit.probing = false;
+    }
+}
+
+impl Drop for CircuitPermit<'_> {
+    fn drop(&mut self) {
+        if !self.reported {
+            self.breaker.lock().probing = false;
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+/// A value accepted in the duration slot of the macros: a `Duration`, or a number of
+/// seconds as in earlier versions (`timeout!(secs { ... })` with `secs: u64`)
+pub trait IntoDuration {
+    fn into_duration(self) -> Duration;
+}
+
+impl IntoDuration for Duration {
+    fn into_duration(self) -> Duration {
+        self
+    }
+}
+
+macro_rules! impl_into_duration_for_ints {
+    ($($int:ty),*) => {
+        $(
+            impl IntoDuration for $int {
+                /// Panics if the number is negative
+                fn into_duration(self) -> Duration {
+                    let secs = u64::try_from(self).unwrap_or_else(|_| panic!("negative duration: {}s", self));
+                    Duration::from_secs(secs)
+                }
+            }
+        )*
+    };
+}
+
+impl_into_duration_for_ints!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl IntoDuration for f32 {
+    /// Panics if the number is negative or not finite
+    fn into_duration(self) -> Duration {
+        Duration::from_secs_f32(self)
+    }
+}
+
+impl IntoDuration for f64 {
+    /// Panics if the number is negative or not finite
+    fn into_duration(self) -> Duration {
+        Duration::from_secs_f64(self)
+    }
+}
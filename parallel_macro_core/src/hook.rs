@@ -0,0 +1,48 @@
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// An error that a `parallel!` branch recovered from with `or` / `or_else`
+pub struct SwallowedError<'a> {
+    // Position of the branch in the macro call
+    pub branch: usize,
+    // Name of the branch, if branches were named
+    pub name: Option<&'static str>,
+    pub error: &'a dyn fmt::Debug,
+}
+
+impl fmt::Display for SwallowedError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "branch `{}` failed: {:?}", name, self.error),
+            None => write!(f, "branch {} failed: {:?}", self.branch, self.error),
+        }
+    }
+}
+
+// Shared so the hook can be called without holding the lock
+type ErrorHook = Arc<dyn Fn(&SwallowedError<'_>) + Send + Sync>;
+
+static ERROR_HOOK: RwLock<Option<ErrorHook>> = RwLock::new(None);
+
+/// Registers a hook that is called every time a branch error is replaced by a default.
+/// Replaces any previously registered hook. Without a hook the errors are dropped silently.
+/// The hook may itself call `set_error_hook` or `clear_error_hook`.
+pub fn set_error_hook<F>(hook: F)
+where
+    F: Fn(&SwallowedError<'_>) + Send + Sync + 'static,
+{
+    *ERROR_HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(hook));
+}
+
+/// Removes the hook registered with `set_error_hook`
+pub fn clear_error_hook() {
+    *ERROR_HOOK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+// Called by the generated code before a branch error is swallowed
+pub fn report_swallowed_error(branch: usize, name: Option<&'static str>, error: &dyn fmt::Debug) {
+    let hook = ERROR_HOOK.read().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(hook) = hook {
+        hook(&SwallowedError { branch, name, error });
+    }
+}
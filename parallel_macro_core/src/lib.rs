@@ -1,17 +1,102 @@
 #![feature(try_trait_v2)]
 use std::ops::{ControlFlow, FromResidual, Try};
+use std::any::Any;
 use std::convert::Infallible;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::time::Duration;
+use futures::FutureExt;
+
+mod cancel;
+mod circuit;
+mod duration;
+mod hook;
+mod multi_error;
+mod one_of;
+mod race;
+mod retry;
+mod runtime;
+mod scope;
+mod spawn;
+mod timeout_error;
+mod vote;
+
+pub use cancel::{CancellationToken, Cancelled};
+pub use circuit::{CircuitBreaker, CircuitConfig, CircuitPermit, CircuitState};
+pub use duration::IntoDuration;
+pub use hook::{clear_error_hook, report_swallowed_error, set_error_hook, SwallowedError};
+pub use multi_error::{BranchFailure, MultiError};
+pub use one_of::{
+    OneOf1, OneOf10, OneOf11, OneOf12, OneOf2, OneOf3, OneOf4, OneOf5, OneOf6, OneOf7, OneOf8, OneOf9,
+};
+pub use race::{hedge, quorum, race, race_ok};
+pub use retry::{Backoff, RetryResult};
+pub use runtime::{
+    block_on, configure_runtime, shared_runtime, try_block_on, RuntimeConfig, RuntimeError, RuntimeFlavor,
+};
+pub use scope::{scope, Scope, TaskScope};
+pub use spawn::{panic_message, resume_branch_panic, spawn_branch, PanicInfo, SpawnError, SpawnedBranch};
+pub use timeout_error::TimeoutError;
+pub use vote::{vote, vote_by, Majority};
 
 pub enum TimeoutResult<T, E> {
     Success(T),
     Error(E),
     TimedOut,
+    // The body panicked; see the `resume_unwind` option to propagate the panic instead
+    Panicked(PanicInfo),
+}
+
+// Outcome of a single parallel_settled! branch
+#[derive(Debug)]
+pub enum Settled<T, E> {
+    Success(T),
+    Error(E),
+    TimedOut,
+    Panicked(Box<dyn Any + Send>),
+}
+
+impl<T, E> Settled<T, E> {
+    pub fn is_success(&self) -> bool {
+        matches!(self, Settled::Success(_))
+    }
+
+    // Returns the value if the branch succeeded
+    pub fn ok(self) -> Option<T> {
+        match self {
+            Settled::Success(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+// Runs a branch to completion, turning errors, timeouts and panics into a Settled outcome
+pub async fn settle<F, T, E>(future: F, limit: Option<Duration>) -> Settled<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let guarded = AssertUnwindSafe(future).catch_unwind();
+
+    let outcome = match limit {
+        Some(limit) => match tokio::time::timeout(limit, guarded).await {
+            Ok(outcome) => outcome,
+            Err(_) => return Settled::TimedOut,
+        },
+        None => guarded.await,
+    };
+
+    match outcome {
+        Ok(Ok(v)) => Settled::Success(v),
+        Ok(Err(e)) => Settled::Error(e),
+        Err(payload) => Settled::Panicked(payload),
+    }
 }
 
 // Define the error type for the residual
 pub enum TimeoutResultError<E> {
     Error(E),
     TimedOut,
+    Panicked(PanicInfo),
 }
 
 // Implement Try
@@ -28,6 +113,7 @@ impl<T, E> Try for TimeoutResult<T, E> {
             TimeoutResult::Success(v) => ControlFlow::Continue(v),
             TimeoutResult::Error(e) => ControlFlow::Break(Err(TimeoutResultError::Error(e))),
             TimeoutResult::TimedOut => ControlFlow::Break(Err(TimeoutResultError::TimedOut)),
+            TimeoutResult::Panicked(info) => ControlFlow::Break(Err(TimeoutResultError::Panicked(info))),
         }
     }
 }
@@ -38,6 +124,7 @@ impl<T, E> FromResidual<Result<Infallible, TimeoutResultError<E>>> for TimeoutRe
         match residual {
             Err(TimeoutResultError::Error(e)) => TimeoutResult::Error(e),
             Err(TimeoutResultError::TimedOut) => TimeoutResult::TimedOut,
+            Err(TimeoutResultError::Panicked(info)) => TimeoutResult::Panicked(info),
             Ok(infallible) => match infallible {},
         }
     }
@@ -0,0 +1,95 @@
+use std::fmt;
+
+/// A failed branch of `parallel_collect!`
+#[derive(Debug)]
+pub struct BranchFailure<E> {
+    // Position of the branch in the macro call
+    pub index: usize,
+    // Name of the branch, if branches were named
+    pub name: Option<&'static str>,
+    pub error: E,
+}
+
+impl<E: fmt::Display> fmt::Display for BranchFailure<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "branch `{}`: {}", name, self.error),
+            None => write!(f, "branch {}: {}", self.index, self.error),
+        }
+    }
+}
+
+/// Every error of a fan-out, so all failed dependencies can be reported at once
+#[derive(Debug)]
+pub struct MultiError<E> {
+    failures: Vec<BranchFailure<E>>,
+}
+
+impl<E> MultiError<E> {
+    pub fn new() -> Self {
+        MultiError { failures: Vec::new() }
+    }
+
+    pub fn push(&mut self, index: usize, name: Option<&'static str>, error: E) {
+        self.failures.push(BranchFailure { index, name, error });
+    }
+
+    pub fn len(&self) -> usize {
+        self.failures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, BranchFailure<E>> {
+        self.failures.iter()
+    }
+
+    // Drops the branch information and keeps just the errors
+    pub fn into_errors(self) -> Vec<E> {
+        self.failures.into_iter().map(|failure| failure.error).collect()
+    }
+}
+
+impl<E> Default for MultiError<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> IntoIterator for MultiError<E> {
+    type Item = BranchFailure<E>;
+    type IntoIter = std::vec::IntoIter<BranchFailure<E>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.failures.into_iter()
+    }
+}
+
+impl<'a, E> IntoIterator for &'a MultiError<E> {
+    type Item = &'a BranchFailure<E>;
+    type IntoIter = std::slice::Iter<'a, BranchFailure<E>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.failures.iter()
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for MultiError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.failures.len() {
+            1 => write!(f, "1 branch failed: ")?,
+            n => write!(f, "{} branches failed: ", n)?,
+        }
+        for (i, failure) in self.failures.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", failure)?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for MultiError<E> {}
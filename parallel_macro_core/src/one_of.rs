@@ -0,0 +1,84 @@
+// Result types of first!: one variant per branch, so every branch can return its own type
+
+macro_rules! same_type {
+    ($_param:ident, $t:ty) => {
+        $t
+    };
+}
+
+macro_rules! one_of {
+    ($(#[$doc:meta])* $name:ident { $($variant:ident = $index:literal),+ }) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum $name<$($variant),+> {
+            $($variant($variant)),+
+        }
+
+        impl<$($variant),+> $name<$($variant),+> {
+            /// Index of the branch that produced the value
+            pub fn index(&self) -> usize {
+                match self {
+                    $($name::$variant(_) => $index),+
+                }
+            }
+        }
+
+        impl<T> $name<$(same_type!($variant, T)),+> {
+            /// The value itself, when every branch returns the same type
+            pub fn into_inner(self) -> T {
+                match self {
+                    $($name::$variant(value) => value),+
+                }
+            }
+        }
+    };
+}
+
+one_of!(
+    /// Output of a first! call with one branch
+    OneOf1 { A = 0 }
+);
+one_of!(
+    /// Output of a first! call with two branches
+    OneOf2 { A = 0, B = 1 }
+);
+one_of!(
+    /// Output of a first! call with three branches
+    OneOf3 { A = 0, B = 1, C = 2 }
+);
+one_of!(
+    /// Output of a first! call with four branches
+    OneOf4 { A = 0, B = 1, C = 2, D = 3 }
+);
+one_of!(
+    /// Output of a first! call with five branches
+    OneOf5 { A = 0, B = 1, C = 2, D = 3, E = 4 }
+);
+one_of!(
+    /// Output of a first! call with six branches
+    OneOf6 { A = 0, B = 1, C = 2, D = 3, E = 4, F = 5 }
+);
+one_of!(
+    /// Output of a first! call with seven branches
+    OneOf7 { A = 0, B = 1, C = 2, D = 3, E = 4, F = 5, G = 6 }
+);
+one_of!(
+    /// Output of a first! call with eight branches
+    OneOf8 { A = 0, B = 1, C = 2, D = 3, E = 4, F = 5, G = 6, H = 7 }
+);
+one_of!(
+    /// Output of a first! call with nine branches
+    OneOf9 { A = 0, B = 1, C = 2, D = 3, E = 4, F = 5, G = 6, H = 7, I = 8 }
+);
+one_of!(
+    /// Output of a first! call with ten branches
+    OneOf10 { A = 0, B = 1, C = 2, D = 3, E = 4, F = 5, G = 6, H = 7, I = 8, J = 9 }
+);
+one_of!(
+    /// Output of a first! call with eleven branches
+    OneOf11 { A = 0, B = 1, C = 2, D = 3, E = 4, F = 5, G = 6, H = 7, I = 8, J = 9, K = 10 }
+);
+one_of!(
+    /// Output of a first! call with twelve branches
+    OneOf12 { A = 0, B = 1, C = 2, D = 3, E = 4, F = 5, G = 6, H = 7, I = 8, J = 9, K = 10, L = 11 }
+);
@@ -0,0 +1,104 @@
+use std::future::Future;
+use std::time::Duration;
+use futures::stream::{FuturesUnordered, StreamExt};
+use futures::FutureExt;
+
+use crate::MultiError;
+
+/// Runs every future and returns the output of the first one to finish, with its index.
+/// The remaining futures are dropped. Returns `None` if there are no futures.
+/// Unlike `tokio::select!` the number of futures doesn't have to be known at compile time.
+pub async fn race<I>(futures: I) -> Option<(usize, <I::Item as Future>::Output)>
+where
+    I: IntoIterator,
+    I::Item: Future,
+{
+    let mut pending = futures
+        .into_iter()
+        .enumerate()
+        .map(|(index, future)| future.map(move |output| (index, output)))
+        .collect::<FuturesUnordered<_>>();
+
+    pending.next().await
+}
+
+/// Like `race`, but skips futures that return `Err`: returns the first `Ok` with its index,
+/// or every error if all of them fail (including when there are no futures at all).
+pub async fn race_ok<I, T, E>(futures: I) -> Result<(usize, T), MultiError<E>>
+where
+    I: IntoIterator,
+    I::Item: Future<Output = Result<T, E>>,
+{
+    let mut pending = futures
+        .into_iter()
+        .enumerate()
+        .map(|(index, future)| future.map(move |output| (index, output)))
+        .collect::<FuturesUnordered<_>>();
+
+    let mut errors = MultiError::new();
+    while let Some((index, output)) = pending.next().await {
+        match output {
+            Ok(value) => return Ok((index, value)),
+            Err(err) => errors.push(index, None, err),
+        }
+    }
+
+    Err(errors)
+}
+
+/// Starts `make()`, and another copy every `delay` while none has finished, up to `max`
+/// copies in total. Returns the output of the first copy to finish; the others are dropped.
+pub async fn hedge<F, Fut>(delay: Duration, max: usize, mut make: F) -> Fut::Output
+where
+    F: FnMut() -> Fut,
+    Fut: Future,
+{
+    let mut attempts = FuturesUnordered::new();
+    attempts.push(make());
+
+    for _ in 1..max {
+        tokio::select! {
+            Some(output) = attempts.next() => return output,
+            _ = tokio::time::sleep(delay) => attempts.push(make()),
+        }
+    }
+
+    attempts.next().await.expect("hedge always starts at least one attempt")
+}
+
+/// Runs every future until `n` of them have succeeded and returns those `n` values in the
+/// order they finished; the remaining futures are dropped. Fails with every error as soon
+/// as too many futures have failed for `n` successes to still be possible.
+/// Panics if `n` is 0 or larger than the number of futures.
+pub async fn quorum<I, T, E>(n: usize, futures: I) -> Result<Vec<T>, MultiError<E>>
+where
+    I: IntoIterator,
+    I::Item: Future<Output = Result<T, E>>,
+{
+    let mut pending = futures
+        .into_iter()
+        .enumerate()
+        .map(|(index, future)| future.map(move |output| (index, output)))
+        .collect::<FuturesUnordered<_>>();
+    assert!(
+        n > 0 && n <= pending.len(),
+        "quorum of {} needs between 1 and {} (the number of futures)",
+        n,
+        pending.len()
+    );
+
+    let mut values = Vec::with_capacity(n);
+    let mut errors = MultiError::new();
+    while values.len() < n {
+        if values.len() + pending.len() < n {
+            return Err(errors);
+        }
+        match pending.next().await {
+            Some((_, Ok(value))) => values.push(value),
+            Some((index, Err(err))) => errors.push(index, None, err),
+            None => return Err(errors),
+        }
+    }
+
+    Ok(values)
+}
@@ -0,0 +1,63 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::TimeoutResult;
+
+/// How long `retry!` waits before the next attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backoff {
+    /// Retry immediately
+    #[default]
+    None,
+    /// Wait the same time before every retry
+    Fixed(Duration),
+    /// Wait `base`, then twice as long before each following retry.
+    /// With `jitter`, every wait is randomly shortened by up to half so that callers
+    /// that failed together don't retry together.
+    Exponential { base: Duration, jitter: bool },
+}
+
+impl Backoff {
+    /// The wait after the given number of failed attempts (starting at 1)
+    pub fn delay(&self, failed_attempts: u32) -> Duration {
+        match *self {
+            Backoff::None => Duration::ZERO,
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { base, jitter } => {
+                let factor = 2u32.saturating_pow(failed_attempts.saturating_sub(1));
+                let delay = base.saturating_mul(factor);
+                if jitter {
+                    delay.mul_f64(1.0 - random_fraction() / 2.0)
+                } else {
+                    delay
+                }
+            },
+        }
+    }
+}
+
+// A number in [0, 1) that differs between calls, from the randomly seeded std hasher
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Outcome of a `retry!` call
+pub struct RetryResult<T, E> {
+    /// Outcome of the last finished attempt: its value or error, or `TimedOut` if the
+    /// attempt ran out of time or the overall deadline ran out before any attempt finished
+    pub outcome: TimeoutResult<T, E>,
+    /// Number of attempts that were started
+    pub attempts: u32,
+    /// Whether the overall deadline ran out while an attempt or backoff was in progress
+    pub deadline_exceeded: bool,
+}
+
+impl<T, E> RetryResult<T, E> {
+    /// The final value or error, dropping the attempt count
+    pub fn into_outcome(self) -> TimeoutResult<T, E> {
+        self.outcome
+    }
+}
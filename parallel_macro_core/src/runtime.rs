@@ -0,0 +1,169 @@
+use std::fmt;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use tokio::runtime::{Builder, Handle, Runtime};
+
+/// Kind of scheduler the shared runtime uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RuntimeFlavor {
+    /// A pool of worker threads
+    #[default]
+    MultiThread,
+    /// Everything runs on the thread that calls `block_on`
+    CurrentThread,
+}
+
+/// Settings for the shared runtime that `block_on` uses outside of a runtime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeConfig {
+    flavor: RuntimeFlavor,
+    worker_threads: Option<usize>,
+    thread_name: String,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        RuntimeConfig {
+            flavor: RuntimeFlavor::default(),
+            worker_threads: None,
+            thread_name: "parallel-macro-worker".to_string(),
+        }
+    }
+}
+
+impl RuntimeConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn flavor(mut self, flavor: RuntimeFlavor) -> Self {
+        self.flavor = flavor;
+        self
+    }
+
+    /// Number of worker threads of a multi-threaded runtime, one per core by default.
+    /// Must be at least 1, and can't be set for a `CurrentThread` runtime.
+    pub fn worker_threads(mut self, worker_threads: usize) -> Self {
+        self.worker_threads = Some(worker_threads);
+        self
+    }
+
+    /// Name of the runtime's threads
+    pub fn thread_name(mut self, thread_name: impl Into<String>) -> Self {
+        self.thread_name = thread_name.into();
+        self
+    }
+
+    fn validate(&self) -> Result<(), RuntimeError> {
+        match (self.flavor, self.worker_threads) {
+            (_, Some(0)) => Err(RuntimeError::InvalidConfig("`worker_threads` must be at least 1")),
+            (RuntimeFlavor::CurrentThread, Some(_)) => Err(RuntimeError::InvalidConfig(
+                "`worker_threads` can't be set for a current_thread runtime",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Builds a runtime with these settings, the way the shared runtime is built on first use
+    pub fn build(&self) -> Result<Runtime, RuntimeError> {
+        self.validate()?;
+        let mut builder = match self.flavor {
+            RuntimeFlavor::MultiThread => Builder::new_multi_thread(),
+            RuntimeFlavor::CurrentThread => Builder::new_current_thread(),
+        };
+        if let Some(worker_threads) = self.worker_threads {
+            builder.worker_threads(worker_threads);
+        }
+        builder
+            .thread_name(self.thread_name.clone())
+            .enable_all()
+            .build()
+            .map_err(RuntimeError::Build)
+    }
+}
+
+/// Why the shared runtime couldn't be configured or started
+#[derive(Debug)]
+pub enum RuntimeError {
+    /// `configure_runtime` was called after the shared runtime had started
+    AlreadyStarted,
+    /// The `RuntimeConfig` has settings that don't go together
+    InvalidConfig(&'static str),
+    /// `try_block_on` was called inside a current_thread runtime, which can't be blocked
+    CurrentThreadRuntime,
+    /// Tokio failed to build the runtime
+    Build(std::io::Error),
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuntimeError::AlreadyStarted => write!(f, "the shared runtime has already started"),
+            RuntimeError::InvalidConfig(reason) => write!(f, "invalid runtime config: {}", reason),
+            RuntimeError::CurrentThreadRuntime => {
+                write!(f, "cannot block inside a current_thread runtime, `.await` the future instead")
+            },
+            RuntimeError::Build(err) => write!(f, "failed to build the shared runtime: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for RuntimeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RuntimeError::AlreadyStarted | RuntimeError::InvalidConfig(_) | RuntimeError::CurrentThreadRuntime => None,
+            RuntimeError::Build(err) => Some(err),
+        }
+    }
+}
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+// Also held while the runtime is built, so configuring and starting can't race
+static CONFIG: Mutex<Option<RuntimeConfig>> = Mutex::new(None);
+
+/// Sets up the shared runtime. Must be called before its first use, i.e. before the
+/// first `block_on` outside of a runtime. Fails if the settings don't go together.
+pub fn configure_runtime(config: RuntimeConfig) -> Result<(), RuntimeError> {
+    config.validate()?;
+    let mut current = CONFIG.lock().unwrap_or_else(|e| e.into_inner());
+    if RUNTIME.get().is_some() {
+        return Err(RuntimeError::AlreadyStarted);
+    }
+    *current = Some(config);
+    Ok(())
+}
+
+/// The shared runtime, started on first use
+pub fn shared_runtime() -> Result<&'static Runtime, RuntimeError> {
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+
+    let config = CONFIG.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+    let runtime = config.clone().unwrap_or_default().build()?;
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+/// Runs `future` to completion from synchronous code and returns its output.
+/// Inside a multi-threaded runtime the current worker is handed off while blocking;
+/// outside of any runtime the shared runtime is used.
+/// Fails inside a current_thread runtime, or if the shared runtime can't be started.
+pub fn try_block_on<F: Future>(future: F) -> Result<F::Output, RuntimeError> {
+    if let Ok(handle) = Handle::try_current() {
+        if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::CurrentThread {
+            return Err(RuntimeError::CurrentThreadRuntime);
+        }
+        return Ok(tokio::task::block_in_place(|| handle.block_on(future)));
+    }
+
+    Ok(shared_runtime()?.block_on(future))
+}
+
+/// Like `try_block_on`, but panics where it would fail.
+/// Used by the timeout macros' `blocking` mode.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    try_block_on(future).unwrap_or_else(|err| panic!("{}", err))
+}
@@ -0,0 +1,161 @@
+use std::future::{poll_fn, Future};
+use std::pin::{pin, Pin};
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::Duration;
+use futures::stream::{FuturesUnordered, StreamExt};
+use tokio::time::Instant;
+
+use crate::TimeoutResult;
+
+type ScopedTask<'a, E> = Pin<Box<dyn Future<Output = Result<(), E>> + Send + 'a>>;
+
+/// Handle for starting tasks inside a `scope`.
+/// Tasks may borrow anything that outlives the scope call; they run concurrently on the
+/// task that awaits the scope (like the branches of `parallel!`), and are all joined or
+/// dropped before the scope returns.
+pub struct Scope<'a, E> {
+    spawned: Arc<Mutex<Vec<ScopedTask<'a, E>>>>,
+    deadline: Option<Instant>,
+}
+
+impl<E> Clone for Scope<'_, E> {
+    fn clone(&self) -> Self {
+        Scope {
+            spawned: Arc::clone(&self.spawned),
+            deadline: self.deadline,
+        }
+    }
+}
+
+impl<'a, E> Scope<'a, E> {
+    /// Starts `task` inside the scope. If it fails, the scope drops every other task and
+    /// returns its error.
+    /// Tasks are polled on the awaiting task, so `Send` isn't needed to run them; it keeps
+    /// `Scope` itself `Send`, so code holding one across an `.await` can still be spawned
+    /// with `tokio::spawn`.
+    pub fn spawn<F>(&self, task: F)
+    where
+        F: Future<Output = Result<(), E>> + Send + 'a,
+    {
+        self.spawned
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Box::pin(task));
+    }
+
+    /// The instant at which the scope and all of its tasks are cancelled, if it has a timeout.
+    /// Pass it on to nested scopes or timeouts so they don't outlive their parent.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    fn take_spawned(&self) -> Vec<ScopedTask<'a, E>> {
+        std::mem::take(&mut *self.spawned.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// Configures a scope before running it, e.g. with a timeout
+#[derive(Debug, Clone, Default)]
+pub struct TaskScope {
+    timeout: Option<Duration>,
+}
+
+impl TaskScope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the scope, including every task spawned in it, once `timeout` has elapsed
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Runs `f` and every task it spawns.
+    /// Returns `TimedOut` if they don't all finish before the timeout.
+    pub async fn run<'a, F, Fut, R, E>(self, f: F) -> TimeoutResult<R, E>
+    where
+        F: FnOnce(Scope<'a, E>) -> Fut,
+        Fut: Future<Output = Result<R, E>>,
+    {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let scope = Scope {
+            spawned: Arc::new(Mutex::new(Vec::new())),
+            deadline,
+        };
+        let body = f(scope.clone());
+
+        let outcome = match deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, drive(scope, body)).await {
+                Ok(outcome) => outcome,
+                Err(_) => return TimeoutResult::TimedOut,
+            },
+            None => drive(scope, body).await,
+        };
+
+        match outcome {
+            Ok(value) => TimeoutResult::Success(value),
+            Err(err) => TimeoutResult::Error(err),
+        }
+    }
+}
+
+/// Runs `f` with a `Scope` and waits for it and every task it spawned, like Go's errgroup.
+/// The first error drops all remaining tasks and is returned.
+pub async fn scope<'a, F, Fut, R, E>(f: F) -> Result<R, E>
+where
+    F: FnOnce(Scope<'a, E>) -> Fut,
+    Fut: Future<Output = Result<R, E>>,
+{
+    let scope = Scope {
+        spawned: Arc::new(Mutex::new(Vec::new())),
+        deadline: None,
+    };
+    let body = f(scope.clone());
+    drive(scope, body).await
+}
+
+// Polls the scope body and its tasks until all of them are done or one of them fails
+async fn drive<'a, Fut, R, E>(scope: Scope<'a, E>, body: Fut) -> Result<R, E>
+where
+    Fut: Future<Output = Result<R, E>>,
+{
+    let mut body = pin!(body);
+    let mut body_output: Option<R> = None;
+    let mut body_done = false;
+    let mut tasks = FuturesUnordered::new();
+
+    poll_fn(|cx| loop {
+        if !body_done {
+            if let Poll::Ready(result) = body.as_mut().poll(cx) {
+                body_done = true;
+                match result {
+                    Ok(value) => body_output = Some(value),
+                    Err(err) => return Poll::Ready(Err(err)),
+                }
+            }
+        }
+
+        tasks.extend(scope.take_spawned());
+
+        while let Poll::Ready(Some(result)) = tasks.poll_next_unpin(cx) {
+            if let Err(err) = result {
+                return Poll::Ready(Err(err));
+            }
+        }
+
+        // Tasks may have spawned more tasks while being polled; those need a first poll too
+        let more_spawned = !scope.spawned.lock().unwrap_or_else(|e| e.into_inner()).is_empty();
+        if more_spawned {
+            continue;
+        }
+
+        if body_done && tasks.is_empty() {
+            return Poll::Ready(Ok(body_output.take().expect("scope body output taken twice")));
+        }
+
+        return Poll::Pending;
+    })
+    .await
+}
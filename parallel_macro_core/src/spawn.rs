@@ -0,0 +1,152 @@
+use std::any::Any;
+use std::fmt;
+use std::future::Future;
+use std::panic::Location;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::task::JoinHandle;
+
+/// Error returned by `parallel_spawn!` when a spawned branch does not finish normally
+#[derive(Debug)]
+pub enum SpawnError {
+    // The branch panicked; `payload` is what was passed to `panic!`
+    Panicked {
+        branch: usize,
+        payload: Box<dyn Any + Send>,
+    },
+    // The branch's task was aborted, e.g. because the runtime is shutting down
+    Cancelled {
+        branch: usize,
+    },
+}
+
+impl SpawnError {
+    /// Index of the branch that failed
+    pub fn branch(&self) -> usize {
+        match self {
+            SpawnError::Panicked { branch, .. } => *branch,
+            SpawnError::Cancelled { branch } => *branch,
+        }
+    }
+}
+
+impl fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpawnError::Panicked { branch, payload } => match panic_message(payload.as_ref()) {
+                Some(msg) => write!(f, "branch {} panicked: {}", branch, msg),
+                None => write!(f, "branch {} panicked", branch),
+            },
+            SpawnError::Cancelled { branch } => write!(f, "branch {} was cancelled", branch),
+        }
+    }
+}
+
+impl std::error::Error for SpawnError {}
+
+/// Extracts the message from a panic payload, if it is a string
+pub fn panic_message(payload: &(dyn Any + Send)) -> Option<&str> {
+    if let Some(msg) = payload.downcast_ref::<&'static str>() {
+        Some(msg)
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        Some(msg.as_str())
+    } else {
+        None
+    }
+}
+
+/// A panic caught from a task, with the location of the macro call that started it
+pub struct PanicInfo {
+    // What was passed to `panic!`
+    pub payload: Box<dyn Any + Send>,
+    pub location: &'static Location<'static>,
+}
+
+impl PanicInfo {
+    pub fn new(payload: Box<dyn Any + Send>, location: &'static Location<'static>) -> Self {
+        PanicInfo { payload, location }
+    }
+
+    /// The panic message, if the payload is a string
+    pub fn message(&self) -> Option<&str> {
+        panic_message(self.payload.as_ref())
+    }
+
+    /// Continues unwinding with the original payload on the current thread
+    pub fn resume(self) -> ! {
+        std::panic::resume_unwind(self.payload)
+    }
+}
+
+impl fmt::Debug for PanicInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PanicInfo")
+            .field("message", &self.message())
+            .field("location", &self.location)
+            .finish()
+    }
+}
+
+impl fmt::Display for PanicInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.message() {
+            Some(msg) => write!(f, "task started at {} panicked: {}", self.location, msg),
+            None => write!(f, "task started at {} panicked", self.location),
+        }
+    }
+}
+
+/// Re-raises the panic of a `par!` branch on the calling thread, prefixed with the branch index.
+/// Payloads that aren't strings are re-raised unchanged so callers can still downcast them.
+pub fn resume_branch_panic(branch: usize, payload: Box<dyn Any + Send>) -> ! {
+    match panic_message(payload.as_ref()) {
+        Some(msg) => panic!("branch {} panicked: {}", branch, msg),
+        None => std::panic::resume_unwind(payload),
+    }
+}
+
+/// A branch running on its own tokio task.
+/// The task is aborted when this handle is dropped, so dropping the
+/// `parallel_spawn!` future doesn't leave its branches running in the background.
+pub struct SpawnedBranch<T> {
+    branch: usize,
+    handle: JoinHandle<T>,
+}
+
+/// Spawns `future` on the current runtime as branch number `branch`
+pub fn spawn_branch<F>(branch: usize, future: F) -> SpawnedBranch<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    SpawnedBranch {
+        branch,
+        handle: tokio::spawn(future),
+    }
+}
+
+impl<T> Future for SpawnedBranch<T> {
+    type Output = Result<T, SpawnError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let branch = self.branch;
+        Pin::new(&mut self.handle).poll(cx).map(|result| {
+            result.map_err(|err| {
+                if err.is_panic() {
+                    SpawnError::Panicked {
+                        branch,
+                        payload: err.into_panic(),
+                    }
+                } else {
+                    SpawnError::Cancelled { branch }
+                }
+            })
+        })
+    }
+}
+
+impl<T> Drop for SpawnedBranch<T> {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
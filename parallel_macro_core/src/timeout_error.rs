@@ -0,0 +1,54 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::PanicInfo;
+
+/// Error returned by `timeout!` and `timeout_value!` when there is no `else` clause
+#[derive(Debug)]
+pub enum TimeoutError {
+    /// The operation didn't finish within `limit`
+    Elapsed {
+        limit: Duration,
+        // Time from the start of the call until it gave up
+        elapsed: Duration,
+        // Set with the macros' `label = "..."` option
+        label: Option<&'static str>,
+    },
+    /// The `timeout_value!` task panicked
+    Panicked {
+        // The panic payload and the macro call that spawned the task
+        info: PanicInfo,
+    },
+    /// The `timeout_value!` task was aborted before it finished, which only happens
+    /// while the runtime shuts down
+    Cancelled,
+}
+
+impl TimeoutError {
+    pub fn is_elapsed(&self) -> bool {
+        matches!(self, TimeoutError::Elapsed { .. })
+    }
+
+    pub fn is_panicked(&self) -> bool {
+        matches!(self, TimeoutError::Panicked { .. })
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, TimeoutError::Cancelled)
+    }
+}
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeoutError::Elapsed { limit, elapsed, label } => match label {
+                Some(label) => write!(f, "`{}` timed out after {:?} (limit {:?})", label, elapsed, limit),
+                None => write!(f, "operation timed out after {:?} (limit {:?})", elapsed, limit),
+            },
+            TimeoutError::Panicked { info } => write!(f, "{}", info),
+            TimeoutError::Cancelled => write!(f, "task was cancelled before it finished"),
+        }
+    }
+}
+
+impl std::error::Error for TimeoutError {}
@@ -0,0 +1,72 @@
+use std::future::Future;
+use futures::stream::{FuturesUnordered, StreamExt};
+use futures::FutureExt;
+
+/// The value most branches of a `vote!` agreed on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Majority<T> {
+    /// The agreed value, as returned by the first branch that produced it
+    pub value: T,
+    /// Indices of the branches that returned the value
+    pub agreeing: Vec<usize>,
+    /// Branches that had returned a different value by the time the majority was reached
+    pub dissenting: Vec<(usize, T)>,
+}
+
+/// Runs every future and returns the output that more than half of them agree on.
+/// Stops as soon as a majority is certain (or impossible) and drops the futures still
+/// running. Returns `None` if no output gets a majority.
+pub async fn vote<I>(futures: I) -> Option<Majority<<I::Item as Future>::Output>>
+where
+    I: IntoIterator,
+    I::Item: Future,
+    <I::Item as Future>::Output: Eq,
+{
+    vote_by(futures, |a, b| a == b).await
+}
+
+/// Like `vote`, with `same` deciding whether two outputs agree
+pub async fn vote_by<I, F>(futures: I, same: F) -> Option<Majority<<I::Item as Future>::Output>>
+where
+    I: IntoIterator,
+    I::Item: Future,
+    F: Fn(&<I::Item as Future>::Output, &<I::Item as Future>::Output) -> bool,
+{
+    let mut pending = futures
+        .into_iter()
+        .enumerate()
+        .map(|(index, future)| future.map(move |output| (index, output)))
+        .collect::<FuturesUnordered<_>>();
+    let needed = pending.len() / 2 + 1;
+
+    // Branch outputs grouped by agreement, compared against each group's first output
+    let mut groups: Vec<Vec<(usize, _)>> = Vec::new();
+    while let Some((index, output)) = pending.next().await {
+        let group = match groups.iter().position(|group| same(&group[0].1, &output)) {
+            Some(group) => {
+                groups[group].push((index, output));
+                group
+            },
+            None => {
+                groups.push(vec![(index, output)]);
+                groups.len() - 1
+            },
+        };
+
+        if groups[group].len() >= needed {
+            let mut agreed = groups.swap_remove(group).into_iter();
+            let (first, value) = agreed.next().expect("vote groups are never empty");
+            let agreeing = std::iter::once(first).chain(agreed.map(|(index, _)| index)).collect();
+            let dissenting = groups.into_iter().flatten().collect();
+            return Some(Majority { value, agreeing, dissenting });
+        }
+
+        // Give up once not even the largest group can reach a majority
+        let largest = groups.iter().map(Vec::len).max().unwrap_or(0);
+        if largest + pending.len() < needed {
+            return None;
+        }
+    }
+
+    None
+}
@@ -52,6 +52,9 @@ impl From<TimeoutResultError<CustomError>> for CustomError {
             TimeoutResultError::TimedOut => CustomError::Timeout(
                 "Operation did not complete within the allotted time".into()
             ),
+            
+            // A panic in the task has no matching variant, so keep its message
+            TimeoutResultError::Panicked(info) => CustomError::Unknown(info.to_string()),
         }
     }
 }
\ No newline at end of file
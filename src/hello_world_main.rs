@@ -2,7 +2,17 @@ mod custom_error;
 
 #[cfg(test)]
 mod tests {
+    pub mod cancel_tests;
+    pub mod compile_fail_tests;
+    pub mod circuit_tests;
+    pub mod dag_tests;
+    pub mod first_tests;
     pub mod parallel_tests;
+    pub mod par_tests;
+    pub mod quorum_tests;
+    pub mod retry_tests;
+    pub mod runtime_tests;
+    pub mod scope_tests;
     pub mod timeout_tests;
 }
 
@@ -14,7 +24,6 @@ use parallel_macro::timeout_value;
 use parallel_macro::timeout_with_result;
 use parallel_macro::first;
 use parallel_macro::my_test_timeout;
-use parallel_macro_core::TimeoutResult;
 use std::time::Duration;
 use custom_error::CustomError;
 
@@ -63,7 +72,7 @@ async fn test_parallel_timeout() {
         get_data()
     } else {
         String::from("too long!")
-    }); 
+    }).await; 
     match result {
         Ok(val) => println!("Sucess: {}", val),
         Err(err) => println!("timeout: {}", err),
@@ -74,7 +83,7 @@ async fn test_parallel_timeout() {
         get_data()
     } else {
         42
-    });
+    }).await;
     println!("result3: {}", result3);
 
     // # Case4: Timeout with value
@@ -82,7 +91,7 @@ async fn test_parallel_timeout() {
         42
     } else {
         String::from("too long #2!")
-    });
+    }).await;
     
     match result4 {
         Ok(val) => println!("Sucess #4: {}", val),
@@ -97,7 +106,7 @@ async fn test_parallel_timeout() {
         }
     } else {
         String::from("too long #2!")
-    });
+    }).await;
 
     match result5 {
         Ok((posts, followers)) => process_data(&posts, &followers),
@@ -131,7 +140,7 @@ async fn do_fallback_2() -> Result<u64, CustomError> {
 async fn test() -> Result<u64, CustomError> {
     let result = timeout_with_result!(1 {
         get_data_3(1100)
-    });
+    }).await;
 
     // If Try is implemented, this would extract the value or return an error
     let value = result?;
@@ -0,0 +1,150 @@
+use parallel_macro::{first, parallel, parallel_settled, timeout_value, timeout_with_result, try_parallel};
+use parallel_macro_core::{CancellationToken, Cancelled, OneOf2, TimeoutResult};
+use std::time::{Duration, Instant};
+
+async fn slow_value(value: i32) -> i32 {
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    value
+}
+
+async fn slow_result(value: i32) -> Result<i32, String> {
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    Ok(value)
+}
+
+// Cancels the token from another task after a short delay
+fn cancel_after(token: &CancellationToken, millis: u64) {
+    let token = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(millis)).await;
+        token.cancel();
+    });
+}
+
+#[tokio::test]
+async fn test_parallel_cancelled() {
+    let token = CancellationToken::new();
+    cancel_after(&token, 50);
+    let started = Instant::now();
+
+    let result = parallel! {
+        cancel = token,
+        slow_value(1),
+        slow_value(2)
+    };
+    
+    assert_eq!(result, Err(Cancelled));
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_parallel_not_cancelled() {
+    let token = CancellationToken::new();
+
+    let result = parallel! {
+        cancel = &token,
+        async { 1 },
+        async { 2 }
+    };
+    
+    assert_eq!(result, Ok((1, 2)));
+}
+
+#[tokio::test]
+async fn test_already_cancelled_token_skips_work() {
+    let token = CancellationToken::new();
+    token.cancel();
+
+    let result = try_parallel! {
+        cancel = token,
+        async { Ok::<_, String>(1) }
+    };
+    
+    assert_eq!(result, Err(Cancelled));
+}
+
+#[tokio::test]
+async fn test_parallel_for_and_settled_cancelled() {
+    let token = CancellationToken::new();
+    cancel_after(&token, 50);
+
+    let values = parallel!(cancel = token, for id in 0..3, limit = 2 => slow_value(id));
+    assert_eq!(values, Err(Cancelled));
+
+    let settled = parallel_settled! {
+        cancel = token,
+        within 10;
+        slow_result(1)
+    };
+    assert!(settled.is_err());
+}
+
+#[tokio::test]
+async fn test_first_cancelled() {
+    let token = CancellationToken::new();
+    cancel_after(&token, 50);
+
+    let result: Result<Result<OneOf2<i32, i32>, String>, Cancelled> = first!(cancel = token, {
+        slow_value(1),
+        slow_value(2)
+    } else {
+        String::from("everything failed")
+    });
+    
+    assert_eq!(result, Err(Cancelled));
+}
+
+#[tokio::test]
+async fn test_timeout_with_result_cancelled_is_not_a_timeout() {
+    let token = CancellationToken::new();
+    cancel_after(&token, 50);
+
+    let result = timeout_with_result!(cancel = token, 2 {
+        slow_result(1)
+    }).await;
+    
+    assert!(matches!(result, Err(Cancelled)));
+}
+
+#[tokio::test]
+async fn test_timeout_with_result_times_out_with_token() {
+    let token = CancellationToken::new();
+
+    let result = timeout_with_result!(cancel = token, 1 {
+        slow_result(1)
+    }).await;
+    
+    assert!(matches!(result, Ok(TimeoutResult::TimedOut)));
+}
+
+#[tokio::test]
+async fn test_timeout_value_cancel_aborts_task() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    let token = CancellationToken::new();
+    cancel_after(&token, 50);
+    let finished = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&finished);
+
+    let result = timeout_value!(cancel = token, 2 {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        flag.store(true, Ordering::SeqCst);
+    }).await;
+    assert!(matches!(result, Err(Cancelled)));
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(!finished.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_cancel_branches_see_caller_variables() {
+    // A caller variable with the same name as the macro's token binding stays visible
+    let cancel_token = CancellationToken::new();
+    let result = parallel! {
+        cancel = cancel_token.clone(),
+        async { cancel_token.is_cancelled() }
+    };
+    
+    assert_eq!(result, Ok((false,)));
+}
@@ -0,0 +1,116 @@
+use parallel_macro::circuit;
+use parallel_macro_core::{CircuitBreaker, CircuitConfig, CircuitState};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+// Each test uses its own breaker name, since breakers are shared by name across the process
+
+async fn payment(calls: &AtomicU32, succeeds: bool) -> Result<&'static str, String> {
+    calls.fetch_add(1, Ordering::SeqCst);
+    if succeeds {
+        Ok("paid")
+    } else {
+        Err("payment service unavailable".to_string())
+    }
+}
+
+#[tokio::test]
+async fn test_circuit_opens_after_failures() {
+    CircuitBreaker::configure("payments-open", CircuitConfig {
+        failure_threshold: 3,
+        cool_down: Duration::from_secs(60),
+    });
+    let calls = AtomicU32::new(0);
+
+    for _ in 0..3 {
+        let result = circuit!("payments-open" { payment(&calls, false) } else Ok("queued"));
+        assert_eq!(result, Err("payment service unavailable".to_string()));
+    }
+    assert_eq!(CircuitBreaker::get("payments-open").state(), CircuitState::Open);
+
+    // Fails fast to the fallback without calling the service
+    let result = circuit!("payments-open" { payment(&calls, true) } else Ok("queued"));
+    assert_eq!(result, Ok("queued"));
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_circuit_counts_timeouts() {
+    CircuitBreaker::configure("payments-slow", CircuitConfig {
+        failure_threshold: 2,
+        cool_down: Duration::from_secs(60),
+    });
+
+    for _ in 0..2 {
+        let result: Result<&str, String> = circuit!("payments-slow" within 20ms {
+            async {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                Ok("paid")
+            }
+        } else Ok("timed out"));
+        assert_eq!(result, Ok("timed out"));
+    }
+
+    assert_eq!(CircuitBreaker::get("payments-slow").state(), CircuitState::Open);
+}
+
+#[tokio::test]
+async fn test_circuit_probes_after_cool_down() {
+    CircuitBreaker::configure("payments-probe", CircuitConfig {
+        failure_threshold: 1,
+        cool_down: Duration::from_millis(50),
+    });
+    let calls = AtomicU32::new(0);
+
+    let _ = circuit!("payments-probe" { payment(&calls, false) } else Ok("queued"));
+    assert_eq!(CircuitBreaker::get("payments-probe").state(), CircuitState::Open);
+
+    // A failed probe opens the circuit again
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let _ = circuit!("payments-probe" { payment(&calls, false) } else Ok("queued"));
+    assert_eq!(CircuitBreaker::get("payments-probe").state(), CircuitState::Open);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    // A successful probe closes it
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let result = circuit!("payments-probe" { payment(&calls, true) } else Ok("queued"));
+    assert_eq!(result, Ok("paid"));
+    assert_eq!(CircuitBreaker::get("payments-probe").state(), CircuitState::Closed);
+}
+
+#[test]
+fn test_circuit_lets_one_probe_through_at_a_time() {
+    let breaker = CircuitBreaker::new(CircuitConfig {
+        failure_threshold: 1,
+        cool_down: Duration::ZERO,
+    });
+    breaker.try_acquire().unwrap().failure();
+
+    let probe = breaker.try_acquire().unwrap();
+    assert_eq!(breaker.state(), CircuitState::HalfOpen);
+    assert!(breaker.try_acquire().is_none());
+
+    // A cancelled probe makes room for the next one
+    drop(probe);
+    assert!(breaker.try_acquire().is_some());
+}
+
+#[test]
+#[should_panic(expected = "`failure_threshold` must be at least 1")]
+fn test_circuit_rejects_zero_threshold() {
+    CircuitBreaker::configure("payments-zero", CircuitConfig {
+        failure_threshold: 0,
+        cool_down: Duration::from_secs(1),
+    });
+}
+
+#[tokio::test]
+async fn test_circuit_body_sees_caller_variables() {
+    // Same names as the macro's own locals
+    let calls = AtomicU32::new(0);
+    let permit = true;
+    let breaker = "queued";
+
+    let result = circuit!("payments-names" { payment(&calls, permit) } else Ok(breaker));
+    assert_eq!(result, Ok("paid"));
+}
@@ -0,0 +1,6 @@
+// Each file in `ui/` must fail to compile with the error in its `.stderr` file
+#[test]
+fn test_compile_errors() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("src/tests/ui/*.rs");
+}
@@ -0,0 +1,66 @@
+use parallel_macro::dag;
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[derive(Debug, Clone)]
+struct User {
+    id: u64,
+}
+
+async fn fetch_user(id: u64) -> User {
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    User { id }
+}
+
+async fn fetch_posts(user: &User) -> Vec<String> {
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    vec![format!("Post of {}", user.id)]
+}
+
+async fn fetch_followers(user: &User) -> Vec<String> {
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    vec![format!("Follower of {}", user.id)]
+}
+
+async fn fetch_recommendations(posts: &[String]) -> usize {
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    posts.len() * 10
+}
+
+async fn fetch_ads() -> &'static str {
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    "ad"
+}
+
+// Paused time advances only when every task is waiting, so the timing doesn't depend on load
+#[tokio::test(start_paused = true)]
+async fn test_dag_runs_independent_nodes_concurrently() {
+    let started = Instant::now();
+
+    let result = dag! {
+        user = fetch_user(7);
+        posts(user) = fetch_posts(&user);
+        followers(user) = fetch_followers(&user);
+        recommendations(posts) = fetch_recommendations(&posts);
+        ads = fetch_ads()
+    };
+    
+    assert_eq!(result.user.id, 7);
+    assert_eq!(result.posts, vec!["Post of 7".to_string()]);
+    assert_eq!(result.followers, vec!["Follower of 7".to_string()]);
+    assert_eq!(result.recommendations, 10);
+    assert_eq!(result.ads, "ad");
+    // user -> posts -> recommendations is the critical path (300ms); everything else overlaps
+    assert_eq!(started.elapsed(), Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn test_dag_nodes_may_be_declared_in_any_order() {
+    let result = dag! {
+        total(a, b) = async move { a + b };
+        a = async { 1 };
+        b(a) = async move { a * 2 };
+    };
+    
+    assert_eq!(result.total, 3);
+}
@@ -0,0 +1,182 @@
+use parallel_macro::{first, first_ok, hedge};
+use parallel_macro_core::{race, race_ok, MultiError, OneOf2, OneOf3};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+async fn delayed_ok(millis: u64) -> Result<u64, String> {
+    tokio::time::sleep(Duration::from_millis(millis)).await;
+    Ok(millis)
+}
+
+async fn delayed_err(millis: u64) -> Result<u64, String> {
+    tokio::time::sleep(Duration::from_millis(millis)).await;
+    Err(format!("failed after {}", millis))
+}
+
+#[tokio::test]
+async fn test_first_returns_fastest_branch() {
+    let result: Result<OneOf2<_, _>, String> = first!({
+        delayed_ok(100),
+        delayed_err(10)
+    } else {
+        String::from("everything failed")
+    });
+    
+    let winner = result.unwrap();
+    assert_eq!(winner.index(), 1);
+    assert_eq!(winner.into_inner(), Err("failed after 10".to_string()));
+}
+
+#[tokio::test]
+async fn test_first_with_different_branch_types() {
+    async fn from_cache() -> Option<&'static str> {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        None
+    }
+
+    async fn from_replica() -> String {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        "replica".to_string()
+    }
+
+    let result = first!({
+        from_cache(),
+        from_replica(),
+        delayed_ok(200)
+    } else {
+        String::from("everything failed")
+    });
+    
+    match result {
+        Ok(OneOf3::B(value)) => assert_eq!(value, "replica"),
+        _ => panic!("Expected the replica to win"),
+    }
+}
+
+#[tokio::test]
+async fn test_first_ok_skips_failed_branches() {
+    let result = first_ok!({
+        delayed_ok(100),
+        delayed_err(10),
+        delayed_ok(50)
+    } else |errors: MultiError<String>| errors.len());
+    
+    assert_eq!(result, Ok(50));
+}
+
+#[tokio::test]
+async fn test_first_branches_see_caller_variables() {
+    // Same name as the macro's own branch futures
+    let future_0 = 7u64;
+    let result = first!({
+        delayed_ok(100),
+        async move { future_0 }
+    } else future_0);
+    
+    match result {
+        Ok(OneOf2::B(value)) => assert_eq!(value, 7),
+        _ => panic!("Expected the second branch to win"),
+    }
+}
+
+#[tokio::test]
+async fn test_first_ok_branches_see_caller_variables() {
+    // Same names as the macro's own bookkeeping
+    let errors = 5;
+    let done_0 = 10;
+    let result = first_ok!({
+        delayed_err(10),
+        delayed_ok(done_0 + errors)
+    } else |_: MultiError<String>| errors);
+    
+    assert_eq!(result, Ok(15));
+}
+
+#[tokio::test]
+async fn test_first_ok_collects_errors_when_all_fail() {
+    let result: Result<u64, String> = first_ok!({
+        delayed_err(20),
+        delayed_err(10)
+    } else |errors: MultiError<String>| errors.to_string());
+    
+    assert_eq!(
+        result,
+        Err("2 branches failed: branch 1: failed after 10; branch 0: failed after 20".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_first_races_a_vec_of_futures() {
+    // More branches than tokio::select! supports
+    let lookups = (0..100).map(|i| delayed_ok(200 - i)).collect::<Vec<_>>();
+
+    let result = first!({ ..lookups } else String::from("no lookups"));
+    
+    assert_eq!(result, Ok((99, Ok(101))));
+}
+
+#[tokio::test]
+async fn test_first_with_no_futures_uses_else() {
+    let lookups: Vec<tokio::time::Sleep> = Vec::new();
+
+    let result = first!({ ..lookups } else "no lookups");
+    
+    assert_eq!(result, Err("no lookups"));
+}
+
+#[tokio::test]
+async fn test_first_ok_races_a_vec_of_futures() {
+    let lookups = [(10, false), (50, true), (30, true)]
+        .into_iter()
+        .map(|(millis, succeeds)| async move {
+            if succeeds { delayed_ok(millis).await } else { delayed_err(millis).await }
+        });
+
+    let result = first_ok!({ ..lookups } else |errors: MultiError<String>| errors.len());
+    
+    assert_eq!(result, Ok((2, 30)));
+}
+
+#[tokio::test]
+async fn test_race_ok_collects_errors_when_all_fail() {
+    assert_eq!(race(vec![delayed_ok(20), delayed_ok(10)]).await, Some((1, Ok(10))));
+
+    let errors = race_ok(vec![delayed_err(20), delayed_err(10)]).await.unwrap_err();
+    
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors.iter().map(|failure| failure.index).collect::<Vec<_>>(), vec![1, 0]);
+}
+
+#[tokio::test]
+async fn test_hedge_starts_backup_when_primary_is_slow() {
+    // The first replica asked is slow, the next one answers quickly
+    let calls = AtomicU64::new(0);
+    let fetch = || async {
+        let call = calls.fetch_add(1, Ordering::SeqCst);
+        let millis = if call == 0 { 1000 } else { 10 };
+        tokio::time::sleep(Duration::from_millis(millis)).await;
+        call
+    };
+
+    let start = std::time::Instant::now();
+    let winner = hedge!(after 50ms, max = 3 { fetch() });
+
+    assert_eq!(winner, 1);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn test_hedge_does_not_start_backup_for_fast_primary() {
+    let calls = AtomicU64::new(0);
+
+    let result = hedge!(after Duration::from_millis(200) {
+        async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            delayed_ok(10).await
+        }
+    });
+
+    assert_eq!(result, Ok(10));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
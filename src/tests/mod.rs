@@ -1,3 +1,8 @@
+pub mod cancel_tests;
+pub mod dag_tests;
+pub mod first_tests;
 pub mod parallel_tests;
+pub mod par_tests;
+pub mod scope_tests;
 pub mod timeout_tests;
 pub mod simple_test; 
\ No newline at end of file
@@ -0,0 +1,73 @@
+use parallel_macro::par;
+
+fn checksum(data: &[u8]) -> u64 {
+    data.iter().map(|&b| b as u64).sum()
+}
+
+fn word_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+#[test]
+fn test_par_borrows_from_stack() {
+    let data = vec![1u8, 2, 3, 4];
+    let text = String::from("hello parallel world");
+
+    let (sum, words) = par! {
+        checksum(&data),
+        word_count(&text)
+    };
+    
+    assert_eq!(sum, 10);
+    assert_eq!(words, 3);
+    // Both values are still usable after the threads are joined
+    assert_eq!(data.len(), 4);
+    assert_eq!(text.len(), 20);
+}
+
+#[test]
+fn test_par_named_branches() {
+    let data = vec![5u8; 10];
+
+    let result = par! {
+        sum: checksum(&data),
+        len: data.len()
+    };
+    
+    assert_eq!(result.sum, 50);
+    assert_eq!(result.len, 10);
+}
+
+#[test]
+#[should_panic(expected = "branch 1 panicked: bad input")]
+fn test_par_reports_panicking_branch() {
+    let _ = par! {
+        word_count("fine"),
+        word_count(if true { panic!("bad input") } else { "" })
+    };
+}
+
+#[test]
+fn test_par_branches_see_caller_scope_variable() {
+    let scope = 3;
+
+    let (a, b) = par! {
+        scope + 1,
+        scope * 2
+    };
+    
+    assert_eq!((a, b), (4, 6));
+}
+
+#[test]
+fn test_par_keeps_non_string_panic_payload() {
+    let result = std::panic::catch_unwind(|| {
+        par! {
+            word_count("fine"),
+            if true { std::panic::panic_any(42u32) } else { 0 }
+        }
+    });
+    
+    let payload = result.unwrap_err();
+    assert_eq!(payload.downcast_ref::<u32>(), Some(&42));
+}
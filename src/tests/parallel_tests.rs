@@ -1,5 +1,6 @@
 use crate::{get_posts, get_followers};
-use parallel_macro::parallel;
+use parallel_macro::{parallel, parallel_collect, parallel_settled, parallel_spawn, try_parallel, timeout_with_result};
+use parallel_macro_core::{Settled, SpawnError, TimeoutResult};
 use std::time::Duration;
 
 async fn quick_task() -> String {
@@ -80,4 +81,335 @@ async fn test_parallel_with_real_functions() {
     assert_eq!(followers.len(), 2);
     assert!(posts[0].contains("Post 1"));
     assert!(followers[0].contains("Follower 1"));
-} 
\ No newline at end of file
+} 
+
+async fn ok_task(value: i32) -> Result<i32, String> {
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    Ok(value)
+}
+
+async fn never_finishing_task() -> Result<i32, String> {
+    tokio::time::sleep(Duration::from_secs(60)).await;
+    Ok(0)
+}
+
+#[tokio::test]
+async fn test_try_parallel_success() {
+    let result = try_parallel! {
+        ok_task(1),
+        ok_task(2)
+    };
+    
+    assert_eq!(result, Ok((1, 2)));
+}
+
+#[tokio::test]
+async fn test_try_parallel_short_circuits_on_error() {
+    let started = std::time::Instant::now();
+    let result: Result<(i32, String), String> = try_parallel! {
+        never_finishing_task(),
+        error_task()
+    };
+    
+    assert_eq!(result, Err("error occurred".to_string()));
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_try_parallel_with_question_mark() {
+    async fn sum() -> Result<i32, String> {
+        let (a, b) = try_parallel! {
+            ok_task(1),
+            ok_task(2)
+        }?;
+        Ok(a + b)
+    }
+    
+    assert_eq!(sum().await, Ok(3));
+}
+
+#[tokio::test]
+async fn test_try_parallel_inside_timeout_with_result() {
+    let result = timeout_with_result!(1 {
+        async {
+            try_parallel! {
+                ok_task(1),
+                never_finishing_task()
+            }
+        }
+    }).await;
+    
+    match result {
+        TimeoutResult::TimedOut => (),
+        _ => panic!("Expected TimedOut"),
+    }
+}
+
+#[tokio::test]
+async fn test_parallel_for_preserves_order() {
+    async fn delayed(value: u64) -> u64 {
+        tokio::time::sleep(Duration::from_millis(100 - value * 10)).await;
+        value
+    }
+
+    let ids = vec![1, 2, 3, 4, 5];
+    let results = parallel!(for id in ids, limit = 2 => delayed(id));
+    
+    assert_eq!(results, vec![1, 2, 3, 4, 5]);
+}
+
+#[tokio::test]
+async fn test_parallel_for_respects_limit() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let running = AtomicUsize::new(0);
+    let max_running = AtomicUsize::new(0);
+    let track = |id: usize| {
+        let running = &running;
+        let max_running = &max_running;
+        async move {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            max_running.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            running.fetch_sub(1, Ordering::SeqCst);
+            id
+        }
+    };
+
+    let results = parallel!(for id in 0..10, limit = 3 => track(id));
+    
+    assert_eq!(results, (0..10).collect::<Vec<_>>());
+    assert_eq!(max_running.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_parallel_for_without_limit() {
+    let ids = vec![1, 2, 3];
+    let posts = parallel!(for id in ids => get_posts(id));
+    
+    assert_eq!(posts.len(), 3);
+    assert!(posts[2][0].contains("user 3"));
+}
+
+#[tokio::test]
+#[should_panic(expected = "`limit` must be at least 1")]
+async fn test_parallel_for_rejects_zero_limit() {
+    let limit = 0;
+    let _ = parallel!(for id in 0..3, limit = limit => get_posts(id));
+}
+
+#[tokio::test]
+async fn test_parallel_named_branches() {
+    let user_id = 123;
+    let result = parallel! {
+        posts: get_posts(user_id),
+        followers: get_followers(user_id)
+    };
+    
+    assert!(result.posts[0].contains("Post 1"));
+    assert!(result.followers[0].contains("Follower 1"));
+}
+
+#[tokio::test]
+async fn test_try_parallel_named_branches() {
+    let result = try_parallel! {
+        first: ok_task(1),
+        second: ok_task(2)
+    }.unwrap();
+    
+    assert_eq!(result.first, 1);
+    assert_eq!(result.second, 2);
+}
+
+#[tokio::test]
+async fn test_parallel_path_expressions_are_not_names() {
+    let (a, b) = parallel! {
+        self::quick_task(),
+        crate::tests::parallel_tests::slow_task()
+    };
+    
+    assert_eq!(a, "quick");
+    assert_eq!(b, "slow");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_parallel_spawn_basic() {
+    let (result1, result2) = parallel_spawn! {
+        quick_task(),
+        slow_task()
+    }.unwrap();
+    
+    assert_eq!(result1, "quick");
+    assert_eq!(result2, "slow");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_parallel_spawn_reports_panicking_branch() {
+    async fn panicking_task() -> String {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        panic!("boom");
+    }
+
+    let result = parallel_spawn! {
+        slow_task(),
+        panicking_task()
+    };
+    
+    match result {
+        Err(err @ SpawnError::Panicked { .. }) => {
+            assert_eq!(err.branch(), 1);
+            assert_eq!(err.to_string(), "branch 1 panicked: boom");
+        },
+        _ => panic!("Expected Panicked"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_parallel_spawn_aborts_branches_when_dropped() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    let finished = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&finished);
+    let task = async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        flag.store(true, Ordering::SeqCst);
+    };
+
+    let result = tokio::time::timeout(Duration::from_millis(50), async {
+        parallel_spawn! { task, quick_task() }
+    }).await;
+    assert!(result.is_err());
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(!finished.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_parallel_settled_reports_every_outcome() {
+    async fn panicking_task() -> Result<i32, String> {
+        panic!("boom");
+    }
+
+    let (ok, err, panicked) = parallel_settled! {
+        ok_task(1),
+        error_task(),
+        panicking_task()
+    };
+    
+    assert_eq!(ok.ok(), Some(1));
+    assert!(matches!(err, Settled::Error(ref e) if e == "error occurred"));
+    assert!(matches!(panicked, Settled::Panicked(_)));
+}
+
+#[tokio::test]
+async fn test_parallel_settled_with_timeout_keeps_fast_branches() {
+    let result = parallel_settled! {
+        within 1;
+        fast: ok_task(1),
+        slow: never_finishing_task()
+    };
+    
+    assert!(result.fast.is_success());
+    assert!(matches!(result.slow, Settled::TimedOut));
+}
+
+#[tokio::test]
+async fn test_parallel_settled_first_branch_named_within() {
+    async fn within(value: i32) -> Result<i32, String> {
+        Ok(value)
+    }
+
+    let (first, second) = parallel_settled! {
+        within(1),
+        ok_task(2)
+    };
+    
+    assert_eq!(first.ok(), Some(1));
+    assert_eq!(second.ok(), Some(2));
+}
+
+#[tokio::test]
+async fn test_parallel_per_branch_timeout_keeps_fast_branches() {
+    let user_id = 123;
+    let (posts, followers) = parallel! {
+        get_posts(user_id) => within 1 else vec![],
+        get_followers(user_id) => within 1 else vec![]
+    };
+    
+    assert!(posts.is_empty());
+    assert_eq!(followers.len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_parallel_spawn_with_per_branch_timeout() {
+    let result = parallel_spawn! {
+        fast: quick_task() => within 1 else "fallback".to_string(),
+        slow: never_finishing_task() => within 1 else Ok(-1)
+    }.unwrap();
+    
+    assert_eq!(result.fast, "quick");
+    assert_eq!(result.slow, Ok(-1));
+}
+
+#[tokio::test]
+async fn test_parallel_or_default_and_or_else() {
+    let (a, b, c) = parallel! {
+        ok_task(1) or 0,
+        error_task() or "default".to_string(),
+        error_task() or_else |e: String| e.to_uppercase()
+    };
+    
+    assert_eq!(a, 1);
+    assert_eq!(b, "default");
+    assert_eq!(c, "ERROR OCCURRED");
+}
+
+#[tokio::test]
+async fn test_parallel_or_reports_swallowed_errors() {
+    use std::sync::Mutex;
+
+    static REPORTED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    parallel_macro_core::set_error_hook(|err| {
+        REPORTED.lock().unwrap().push(err.to_string());
+        // Hooks may replace themselves while they run
+        parallel_macro_core::set_error_hook(|_| {});
+    });
+
+    let result = parallel! {
+        value: ok_task(1) or 0,
+        message: error_task() or String::new() => within 1 else String::new()
+    };
+    parallel_macro_core::clear_error_hook();
+    
+    assert_eq!(result.value, 1);
+    assert_eq!(result.message, "");
+    assert!(REPORTED.lock().unwrap().contains(&"branch `message` failed: \"error occurred\"".to_string()));
+}
+
+#[tokio::test]
+async fn test_parallel_collect_success() {
+    let result = parallel_collect! {
+        ok_task(1),
+        ok_task(2)
+    };
+    
+    assert_eq!(result.unwrap(), (1, 2));
+}
+
+#[tokio::test]
+async fn test_parallel_collect_keeps_every_error() {
+    let result = parallel_collect! {
+        posts: error_task(),
+        count: ok_task(1),
+        followers: error_task()
+    };
+    
+    let errors = result.unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors.iter().map(|f| f.index).collect::<Vec<_>>(), vec![0, 2]);
+    assert_eq!(
+        errors.to_string(),
+        "2 branches failed: branch `posts`: error occurred; branch `followers`: error occurred"
+    );
+}
\ No newline at end of file
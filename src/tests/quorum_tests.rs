@@ -0,0 +1,160 @@
+use parallel_macro::{quorum, vote};
+use parallel_macro_core::{MultiError, TimeoutResult};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+async fn replica_write(name: &'static str, millis: u64, succeeds: bool) -> Result<&'static str, String> {
+    tokio::time::sleep(Duration::from_millis(millis)).await;
+    if succeeds {
+        Ok(name)
+    } else {
+        Err(format!("{} rejected the write", name))
+    }
+}
+
+#[tokio::test]
+async fn test_quorum_returns_first_successes() {
+    let result = quorum!(2 of {
+        replica_write("a", 100, true),
+        replica_write("b", 10, false),
+        replica_write("c", 30, true),
+        replica_write("d", 50, true)
+    });
+
+    assert_eq!(result.unwrap(), vec!["c", "d"]);
+}
+
+#[tokio::test]
+async fn test_quorum_drops_remaining_branches() {
+    let finished = AtomicU64::new(0);
+    let write = |millis: u64| {
+        let finished = &finished;
+        async move {
+            tokio::time::sleep(Duration::from_millis(millis)).await;
+            finished.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, String>(millis)
+        }
+    };
+
+    let result = quorum!(2 of { write(10), write(20), write(500) });
+
+    assert_eq!(result.unwrap(), vec![10, 20]);
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    assert_eq!(finished.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_quorum_fails_once_unreachable() {
+    let start = std::time::Instant::now();
+
+    let result: Result<Vec<&str>, MultiError<String>> = quorum!(2 of {
+        replica_write("a", 10, false),
+        replica_write("b", 20, false),
+        replica_write("c", 1000, true)
+    });
+
+    // Two failures out of three make two successes impossible, so c isn't waited for
+    let errors = result.unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn test_quorum_within_time_limit() {
+    let result = quorum!(2 of {
+        replica_write("a", 10, true),
+        replica_write("b", 1000, true),
+        replica_write("c", 1000, true)
+    } within 100ms);
+
+    match result {
+        TimeoutResult::TimedOut => {},
+        _ => panic!("Expected the quorum to time out"),
+    }
+
+    let result = quorum!(2 of {
+        replica_write("a", 10, true),
+        replica_write("b", 20, true)
+    } within 1s);
+
+    match result {
+        TimeoutResult::Success(values) => assert_eq!(values, vec!["a", "b"]),
+        _ => panic!("Expected the quorum to be reached"),
+    }
+}
+
+#[tokio::test]
+#[should_panic(expected = "quorum of 3 needs between 1 and 2")]
+async fn test_quorum_rejects_unreachable_count() {
+    let required = 3;
+    let _ = quorum!(required of {
+        replica_write("a", 10, true),
+        replica_write("b", 20, true)
+    });
+}
+
+async fn reading(value: i64, millis: u64) -> i64 {
+    tokio::time::sleep(Duration::from_millis(millis)).await;
+    value
+}
+
+#[tokio::test]
+async fn test_vote_returns_majority_and_dissenters() {
+    let result = vote! {
+        reading(42, 10),
+        reading(7, 20),
+        reading(42, 30)
+    };
+
+    let majority = result.unwrap();
+    assert_eq!(majority.value, 42);
+    assert_eq!(majority.agreeing, vec![0, 2]);
+    assert_eq!(majority.dissenting, vec![(1, 7)]);
+}
+
+#[tokio::test]
+async fn test_vote_stops_once_majority_is_certain() {
+    let start = std::time::Instant::now();
+
+    let majority = vote! {
+        reading(1, 10),
+        reading(1, 20),
+        reading(1, 1000)
+    }
+    .unwrap();
+
+    assert_eq!(majority.agreeing, vec![0, 1]);
+    assert!(majority.dissenting.is_empty());
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn test_vote_without_majority() {
+    let start = std::time::Instant::now();
+
+    let result = vote! {
+        reading(1, 10),
+        reading(2, 20),
+        reading(3, 30),
+        reading(1, 1000)
+    };
+
+    // After three different readings the last branch can't make a majority of three
+    assert_eq!(result, None);
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn test_vote_with_custom_comparator() {
+    // Readings within 5 of each other count as the same
+    let majority = vote! {
+        compare = |a: &i64, b: &i64| (a - b).abs() <= 5,
+        reading(100, 10),
+        reading(250, 20),
+        reading(103, 30)
+    }
+    .unwrap();
+
+    assert_eq!(majority.value, 100);
+    assert_eq!(majority.dissenting, vec![(1, 250)]);
+}
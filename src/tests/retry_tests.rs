@@ -0,0 +1,150 @@
+use parallel_macro::retry;
+use parallel_macro_core::{Backoff, TimeoutResult};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+// Fails until it has been called `succeed_on` times
+async fn flaky(calls: &AtomicU32, succeed_on: u32) -> Result<u32, String> {
+    let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+    if call >= succeed_on {
+        Ok(call)
+    } else {
+        Err(format!("call {} failed", call))
+    }
+}
+
+#[tokio::test]
+async fn test_retry_until_success() {
+    let calls = AtomicU32::new(0);
+
+    let result = retry!(attempts = 5, backoff = fixed(10ms) { flaky(&calls, 3) });
+
+    assert_eq!(result.attempts, 3);
+    match result.outcome {
+        TimeoutResult::Success(call) => assert_eq!(call, 3),
+        _ => panic!("Expected the third attempt to succeed"),
+    }
+}
+
+#[tokio::test]
+async fn test_retry_returns_last_error() {
+    let calls = AtomicU32::new(0);
+
+    let result = retry!(attempts = 3 { flaky(&calls, 10) });
+
+    assert_eq!(result.attempts, 3);
+    match result.outcome {
+        TimeoutResult::Error(err) => assert_eq!(err, "call 3 failed"),
+        _ => panic!("Expected the last error"),
+    }
+}
+
+#[tokio::test]
+async fn test_retry_only_retryable_errors() {
+    let calls = AtomicU32::new(0);
+
+    let result = retry!(attempts = 5, retry_if = |err: &String| !err.contains("call 2") {
+        flaky(&calls, 10)
+    });
+
+    assert_eq!(result.attempts, 2);
+    assert!(matches!(result.outcome, TimeoutResult::Error(_)));
+}
+
+#[tokio::test]
+async fn test_retry_per_attempt_timeout() {
+    let calls = AtomicU32::new(0);
+
+    // The first attempt hangs, the second answers right away
+    let result = retry!(attempts = 3, per_attempt = 50ms {
+        async {
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            if call == 0 {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+            }
+            Ok::<_, String>(call)
+        }
+    });
+
+    assert_eq!(result.attempts, 2);
+    assert!(matches!(result.outcome, TimeoutResult::Success(1)));
+}
+
+#[tokio::test]
+async fn test_retry_deadline() {
+    let calls = AtomicU32::new(0);
+    let start = Instant::now();
+
+    let result = retry!(attempts = 100, backoff = exponential(20ms, jitter), deadline = 200ms {
+        flaky(&calls, 1000)
+    });
+
+    // The deadline keeps the error of the last attempt that finished
+    assert!(result.deadline_exceeded);
+    match result.outcome {
+        TimeoutResult::Error(err) => assert_eq!(err, format!("call {} failed", result.attempts)),
+        _ => panic!("Expected the last error"),
+    }
+    assert!(result.attempts > 1 && result.attempts < 100);
+    assert!(start.elapsed() < Duration::from_millis(400));
+}
+
+#[tokio::test]
+async fn test_retry_deadline_before_first_attempt_finishes() {
+    let result = retry!(deadline = 50ms {
+        async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Ok::<u32, String>(1)
+        }
+    });
+
+    assert!(result.deadline_exceeded);
+    assert!(matches!(result.outcome, TimeoutResult::TimedOut));
+    assert_eq!(result.attempts, 1);
+}
+
+#[tokio::test]
+async fn test_retry_body_sees_caller_variables() {
+    // Same names as the macro's own locals
+    let attempts = 2u32;
+    let backoff = 1u32;
+    let result = retry!(retry_if = |_: &String| backoff == 1 {
+        async move { Ok::<u32, String>(attempts) }
+    });
+
+    assert!(!result.deadline_exceeded);
+    assert!(matches!(result.outcome, TimeoutResult::Success(2)));
+}
+
+#[tokio::test]
+async fn test_retry_panicking_attempt_is_not_retried() {
+    let calls = AtomicU32::new(0);
+    let result = retry!(attempts = 3 {
+        async {
+            // Only the first call panics, a retry would succeed
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                panic!("attempt failed hard");
+            }
+            Ok::<u32, String>(1)
+        }
+    });
+
+    assert_eq!(result.attempts, 1);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(matches!(result.outcome, TimeoutResult::Panicked(_)));
+}
+
+#[test]
+fn test_backoff_delays() {
+    let base = Duration::from_millis(100);
+    let exponential = Backoff::Exponential { base, jitter: false };
+    assert_eq!(exponential.delay(1), base);
+    assert_eq!(exponential.delay(4), base * 8);
+    assert_eq!(Backoff::Fixed(base).delay(4), base);
+
+    let jittered = Backoff::Exponential { base, jitter: true };
+    for attempt in 1..5 {
+        let delay = jittered.delay(attempt);
+        assert!(delay <= exponential.delay(attempt) && delay >= exponential.delay(attempt) / 2);
+    }
+}
@@ -0,0 +1,92 @@
+use parallel_macro::timeout_with_result;
+use parallel_macro_core::{
+    block_on, configure_runtime, shared_runtime, try_block_on, RuntimeConfig, RuntimeError, RuntimeFlavor, TimeoutResult,
+};
+use std::time::Duration;
+
+#[test]
+fn test_block_on_from_sync_code() {
+    let value = block_on(async {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        42
+    });
+
+    assert_eq!(value, 42);
+}
+
+#[test]
+fn test_blocking_macros_share_one_runtime() {
+    // Tasks spawned by the blocking calls run on the shared runtime's workers
+    for _ in 0..3 {
+        let result = timeout_with_result!(blocking, 1s {
+            async {
+                let name = tokio::spawn(async { std::thread::current().name().map(String::from) })
+                    .await
+                    .unwrap();
+                Ok::<_, String>(name)
+            }
+        });
+        match result {
+            TimeoutResult::Success(name) => assert_eq!(name.as_deref(), Some("parallel-macro-worker")),
+            _ => panic!("Expected Success"),
+        }
+    }
+}
+
+#[test]
+fn test_configure_after_start_fails() {
+    shared_runtime().unwrap();
+
+    let result = configure_runtime(RuntimeConfig::new().worker_threads(2).thread_name("too-late"));
+
+    assert!(matches!(result, Err(RuntimeError::AlreadyStarted)));
+}
+
+// The shared runtime can only be configured once per process, so the settings are
+// checked on runtimes built directly from the config
+
+#[test]
+fn test_runtime_config_multi_thread() {
+    let runtime = RuntimeConfig::new().worker_threads(2).thread_name("io").build().unwrap();
+
+    assert_eq!(runtime.handle().runtime_flavor(), tokio::runtime::RuntimeFlavor::MultiThread);
+    assert_eq!(runtime.metrics().num_workers(), 2);
+    let task = runtime.spawn(async { std::thread::current().name().map(String::from) });
+    let name = runtime.block_on(task).unwrap();
+    assert_eq!(name.as_deref(), Some("io"));
+}
+
+#[test]
+fn test_runtime_config_current_thread() {
+    let runtime = RuntimeConfig::new().flavor(RuntimeFlavor::CurrentThread).build().unwrap();
+
+    assert_eq!(runtime.handle().runtime_flavor(), tokio::runtime::RuntimeFlavor::CurrentThread);
+    assert_eq!(runtime.block_on(async { 1 + 1 }), 2);
+}
+
+#[test]
+fn test_runtime_config_rejects_invalid_worker_threads() {
+    let zero = RuntimeConfig::new().worker_threads(0);
+    assert!(matches!(zero.build(), Err(RuntimeError::InvalidConfig(_))));
+    assert!(matches!(configure_runtime(zero), Err(RuntimeError::InvalidConfig(_))));
+
+    let current_thread = RuntimeConfig::new().flavor(RuntimeFlavor::CurrentThread).worker_threads(2);
+    assert!(matches!(current_thread.build(), Err(RuntimeError::InvalidConfig(_))));
+    assert!(matches!(configure_runtime(current_thread), Err(RuntimeError::InvalidConfig(_))));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_block_on_inside_multi_thread_runtime() {
+    assert_eq!(block_on(async { 1 + 1 }), 2);
+}
+
+#[tokio::test]
+async fn test_try_block_on_inside_current_thread_runtime_fails() {
+    assert!(matches!(try_block_on(async {}), Err(RuntimeError::CurrentThreadRuntime)));
+}
+
+#[tokio::test]
+#[should_panic(expected = "cannot block inside a current_thread runtime")]
+async fn test_block_on_inside_current_thread_runtime_panics() {
+    block_on(async {});
+}
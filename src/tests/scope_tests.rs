@@ -0,0 +1,99 @@
+use parallel_macro_core::{scope, TaskScope, TimeoutResult};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+async fn fetch_len(item: &str) -> usize {
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    item.len()
+}
+
+#[tokio::test]
+async fn test_scope_joins_borrowing_tasks() {
+    let items = vec!["a".to_string(), "bb".to_string(), "ccc".to_string()];
+    let total = AtomicUsize::new(0);
+
+    let result: Result<(), String> = scope(|s| {
+        let items = &items;
+        let total = &total;
+        async move {
+            for item in items {
+                s.spawn(async move {
+                    total.fetch_add(fetch_len(item).await, Ordering::SeqCst);
+                    Ok(())
+                });
+            }
+            Ok(())
+        }
+    }).await;
+    
+    assert_eq!(result, Ok(()));
+    assert_eq!(total.load(Ordering::SeqCst), 6);
+}
+
+#[tokio::test]
+async fn test_scope_first_error_cancels_siblings() {
+    let finished = AtomicUsize::new(0);
+    let started = Instant::now();
+
+    let result = scope(|s| {
+        let finished = &finished;
+        async move {
+            s.spawn(async move {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                finished.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            });
+            s.spawn(async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Err("failed".to_string())
+            });
+            Ok(())
+        }
+    }).await;
+    
+    assert_eq!(result, Err("failed".to_string()));
+    assert_eq!(finished.load(Ordering::SeqCst), 0);
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_scope_tasks_can_spawn_tasks() {
+    let seen = Mutex::new(Vec::new());
+
+    let result: Result<usize, String> = scope(|s| {
+        let seen = &seen;
+        async move {
+            let inner = s.clone();
+            s.spawn(async move {
+                seen.lock().unwrap().push("parent");
+                inner.spawn(async move {
+                    seen.lock().unwrap().push("child");
+                    Ok(())
+                });
+                Ok(())
+            });
+            Ok(42)
+        }
+    }).await;
+    
+    assert_eq!(result, Ok(42));
+    assert_eq!(*seen.lock().unwrap(), vec!["parent", "child"]);
+}
+
+#[tokio::test]
+async fn test_task_scope_timeout_applies_to_children() {
+    let result = TaskScope::new()
+        .timeout(Duration::from_millis(100))
+        .run(|s| async move {
+            assert!(s.deadline().is_some());
+            s.spawn(async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok::<_, String>(())
+            });
+            Ok(())
+        })
+        .await;
+    
+    assert!(matches!(result, TimeoutResult::TimedOut));
+}
@@ -1,6 +1,6 @@
 use crate::custom_error::CustomError;
-use parallel_macro::timeout_with_result;
-use parallel_macro_core::TimeoutResult;
+use parallel_macro::{timeout, timeout_fallback, timeout_value, timeout_with_result};
+use parallel_macro_core::{TimeoutError, TimeoutResult};
 use std::time::Duration;
 
 async fn quick_success_task() -> Result<i32, CustomError> {
@@ -28,11 +28,11 @@ async fn timeout_task() -> Result<i32, CustomError> {
     Ok(999)
 }
 
-#[tokio::test(flavor = "multi_thread")]
+#[tokio::test]
 async fn test_timeout_with_result_success() {
     let result = timeout_with_result!(1 {
         quick_success_task()
-    });
+    }).await;
     
     match result {
         TimeoutResult::Success(value) => assert_eq!(value, 42),
@@ -40,11 +40,11 @@ async fn test_timeout_with_result_success() {
     }
 }
 
-#[tokio::test(flavor = "multi_thread")]
+#[tokio::test]
 async fn test_timeout_with_result_error() {
     let result = timeout_with_result!(1 {
         quick_error_task()
-    });
+    }).await;
     
     match result {
         TimeoutResult::Error(err) => {
@@ -57,11 +57,11 @@ async fn test_timeout_with_result_error() {
     }
 }
 
-#[tokio::test(flavor = "multi_thread")]
+#[tokio::test]
 async fn test_timeout_with_result_timeout() {
     let result = timeout_with_result!(1 {
         timeout_task()
-    });
+    }).await;
     
     match result {
         TimeoutResult::TimedOut => (),
@@ -69,11 +69,11 @@ async fn test_timeout_with_result_timeout() {
     }
 }
 
-#[tokio::test(flavor = "multi_thread")]
+#[tokio::test]
 async fn test_timeout_with_result_slow_success() {
     let result = timeout_with_result!(1 {
         slow_success_task()
-    });
+    }).await;
     
     match result {
         TimeoutResult::Success(value) => assert_eq!(value, 100),
@@ -81,11 +81,11 @@ async fn test_timeout_with_result_slow_success() {
     }
 }
 
-#[tokio::test(flavor = "multi_thread")]
+#[tokio::test]
 async fn test_timeout_with_result_slow_error() {
     let result = timeout_with_result!(1 {
         slow_error_task()
-    });
+    }).await;
     
     match result {
         TimeoutResult::Error(err) => {
@@ -98,16 +98,254 @@ async fn test_timeout_with_result_slow_error() {
     }
 }
 
-#[tokio::test(flavor = "multi_thread")]
+#[tokio::test]
 async fn test_timeout_with_result_with_fallback() {
     let result = timeout_with_result!(1 {
         timeout_task()
     } else {
         Ok(123)
-    });
+    }).await;
     
     match result {
         TimeoutResult::Success(value) => assert_eq!(value, 123),
         _ => panic!("Expected Success(123) from fallback"),
     }
-} 
\ No newline at end of file
+} 
+#[tokio::test]
+async fn test_timeout_with_millisecond_duration() {
+    // Both limits are longer than the quick task (50ms) but shorter than the slow one (200ms)
+    let result = timeout_with_result!(100ms {
+        slow_success_task()
+    }).await;
+    assert!(matches!(result, TimeoutResult::TimedOut));
+
+    let result = timeout_with_result!(150ms {
+        quick_success_task()
+    }).await;
+    assert!(matches!(result, TimeoutResult::Success(42)));
+}
+
+#[tokio::test]
+async fn test_timeout_with_fractional_duration() {
+    let result = timeout_with_result!(0.1 {
+        slow_success_task()
+    }).await;
+    assert!(matches!(result, TimeoutResult::TimedOut));
+
+    let result = timeout_with_result!(0.5s {
+        slow_success_task()
+    }).await;
+    assert!(matches!(result, TimeoutResult::Success(100)));
+}
+
+#[tokio::test]
+async fn test_timeout_with_duration_expression() {
+    let limit = Duration::from_millis(100);
+
+    let result = timeout_with_result!(limit {
+        slow_success_task()
+    }).await;
+    
+    assert!(matches!(result, TimeoutResult::TimedOut));
+}
+
+#[tokio::test]
+async fn test_timeout_with_numeric_seconds() {
+    // Numbers without a unit are seconds, whether literal or computed
+    let secs: u64 = 1;
+    let result = timeout_with_result!(secs {
+        slow_success_task()
+    }).await;
+    assert!(matches!(result, TimeoutResult::Success(100)));
+    
+    let result = timeout_fallback!(1u64 {
+        async { slow_success_task().await.unwrap() }
+    } else -1).await;
+    assert_eq!(result, 100);
+}
+
+#[tokio::test]
+async fn test_timeout_body_sees_caller_variables() {
+    // The macros' own locals must not shadow variables of the same name in the body
+    let limit = 3;
+    let started = 4;
+    let result = timeout!(1s {
+        async move { limit + started }
+    }).await;
+    
+    assert_eq!(result.unwrap(), 7);
+}
+
+#[tokio::test]
+async fn test_timeout_fallback_sees_caller_variables() {
+    // Same names as the macros' own futures and task
+    let body_future = 1;
+    let timeout_future = 2;
+    let task = 3;
+    
+    let result: Result<Result<i32, CustomError>, i32> = timeout!(10ms { slow_success_task() } else timeout_future).await;
+    assert_eq!(result.unwrap_err(), 2);
+    
+    let result = timeout_fallback!(10ms { async { slow_success_task().await.unwrap() } } else body_future).await;
+    assert_eq!(result, 1);
+    
+    let result: Result<i32, i32> = timeout_value!(10ms {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        0
+    } else task).await;
+    assert_eq!(result.unwrap_err(), 3);
+    
+    let result = timeout_with_result!(10ms { slow_success_task() } else Ok(timeout_future)).await;
+    assert!(matches!(result, TimeoutResult::Success(2)));
+}
+
+#[tokio::test]
+async fn test_timeout_error_reports_duration() {
+    let result = timeout!(100ms {
+        async {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            1
+        }
+    }).await;
+    match result {
+        Err(TimeoutError::Elapsed { limit, elapsed, label }) => {
+            assert_eq!(limit, Duration::from_millis(100));
+            assert!(elapsed >= limit);
+            assert_eq!(label, None);
+        },
+        _ => panic!("Expected Elapsed"),
+    }
+
+    let result = timeout_value!(1.5s {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        1
+    }).await;
+    assert!(matches!(result, Err(TimeoutError::Elapsed { limit, .. }) if limit == Duration::from_millis(1500)));
+}
+
+#[tokio::test]
+async fn test_timeout_error_label() {
+    let result = timeout!(label = "load_profile", 50ms {
+        tokio::time::sleep(Duration::from_secs(1))
+    }).await;
+    
+    let err = result.unwrap_err();
+    assert!(matches!(err, TimeoutError::Elapsed { label: Some("load_profile"), .. }));
+    assert!(err.to_string().starts_with("`load_profile` timed out after"));
+}
+
+#[tokio::test]
+async fn test_timeout_value_reports_panic() {
+    // `line!()` sits on the line of the macro call, which is reported as the spawn location
+    let (call_line, result): (u32, Result<i32, TimeoutError>) = (line!(), timeout_value!(1s {
+        panic!("lookup failed")
+    }).await);
+    
+    let err = result.unwrap_err();
+    assert!(err.is_panicked());
+    match &err {
+        TimeoutError::Panicked { info } => {
+            assert_eq!(info.message(), Some("lookup failed"));
+            assert!(info.location.file().ends_with("timeout_tests.rs"));
+            assert_eq!(info.location.line(), call_line);
+        },
+        _ => panic!("Expected Panicked"),
+    }
+    assert!(err.to_string().ends_with("panicked: lookup failed"));
+}
+
+#[tokio::test]
+async fn test_timeout_with_result_reports_panic() {
+    let (call_line, result): (u32, TimeoutResult<i32, CustomError>) = (line!(), timeout_with_result!(1s {
+        async { panic!("lookup failed") }
+    }).await);
+    
+    match result {
+        TimeoutResult::Panicked(info) => {
+            assert_eq!(info.message(), Some("lookup failed"));
+            assert_eq!(info.location.line(), call_line);
+        },
+        _ => panic!("Expected Panicked"),
+    }
+}
+
+#[tokio::test]
+#[should_panic(expected = "lookup failed")]
+async fn test_timeout_value_resume_unwind() {
+    let _: Result<i32, TimeoutError> = timeout_value!(resume_unwind, 1s {
+        panic!("lookup failed")
+    }).await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "lookup failed")]
+async fn test_timeout_value_with_fallback_resumes_panic() {
+    // A panic isn't a timeout, so it doesn't turn into the fallback
+    let _: Result<i32, i32> = timeout_value!(1s {
+        panic!("lookup failed")
+    } else -1).await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "lookup failed")]
+async fn test_timeout_with_result_resume_unwind() {
+    let _: TimeoutResult<i32, CustomError> = timeout_with_result!(resume_unwind, 1s {
+        async { panic!("lookup failed") }
+    }).await;
+}
+
+#[test]
+fn test_timeout_value_reports_cancelled_task() {
+    // The task runs on a second runtime that shuts down while the call waits for it
+    let tasks = tokio::runtime::Runtime::new().unwrap();
+    let caller = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    let handle = tasks.handle().clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        tasks.shutdown_background();
+    });
+
+    let result: Result<i32, TimeoutError> = caller.block_on(async {
+        let _tasks = handle.enter();
+        timeout_value!(5s {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            1
+        }).await
+    });
+
+    let err = result.unwrap_err();
+    assert!(err.is_cancelled());
+    assert_eq!(err.to_string(), "task was cancelled before it finished");
+}
+
+#[tokio::test]
+async fn test_timeouts_compose_inside_other_futures() {
+    // The expansions are futures, so they can be joined without blocking the runtime
+    let (quick, slow) = tokio::join!(
+        timeout_fallback!(100ms { async { quick_success_task().await.unwrap() } } else -1),
+        timeout_fallback!(100ms { async { slow_success_task().await.unwrap() } } else -1),
+    );
+    
+    assert_eq!((quick, slow), (42, -1));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_blocking_timeout_in_runtime() {
+    let result = timeout_with_result!(blocking, 1 {
+        quick_success_task()
+    });
+    
+    assert!(matches!(result, TimeoutResult::Success(42)));
+}
+
+#[test]
+fn test_blocking_timeout_without_runtime() {
+    let result = timeout_fallback!(blocking, 100ms {
+        async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            1
+        }
+    } else 2);
+    
+    assert_eq!(result, 2);
+}
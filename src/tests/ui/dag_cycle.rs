@@ -0,0 +1,10 @@
+use parallel_macro::dag;
+
+#[tokio::main]
+async fn main() {
+    let _ = dag! {
+        a(c) = async move { c + 1 };
+        b(a) = async move { a + 1 };
+        c(b) = async move { b + 1 };
+    };
+}
@@ -0,0 +1,9 @@
+use parallel_macro::dag;
+
+#[tokio::main]
+async fn main() {
+    let _ = dag! {
+        user = async { 7 };
+        posts(usr) = async move { usr * 2 };
+    };
+}
@@ -0,0 +1,6 @@
+use parallel_macro::parallel;
+
+#[tokio::main]
+async fn main() {
+    let _ = parallel!(for id in 0..3, limit = 0 => async move { id });
+}
@@ -0,0 +1,11 @@
+use parallel_macro::quorum;
+
+async fn write() -> Result<(), String> {
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let _ = quorum!(0 of { write(), write() });
+    let _ = quorum!(3 of { write(), write() });
+}
@@ -0,0 +1,10 @@
+use parallel_macro::retry;
+
+async fn call() -> Result<(), String> {
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let _ = retry!(attempts = 0 { call() });
+}
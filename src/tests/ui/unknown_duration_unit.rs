@@ -0,0 +1,6 @@
+use parallel_macro::timeout;
+
+#[tokio::main]
+async fn main() {
+    let _ = timeout!(5parsecs { async { 1 } }).await;
+}